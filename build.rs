@@ -0,0 +1,70 @@
+//! Generates the dispatch table of solutions that `src/main.rs` includes with the `registry` feature.
+//! Every `src/bin/<year>_<day>.rs` is compiled as a module of the CLI binary, so that `cargo all` and
+//! `cargo time` can call the solutions directly instead of spawning one binary per day.
+use std::{env, fs, path::Path};
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let bin_dir = Path::new(&manifest_dir).join("src").join("bin");
+
+    // only watch the solutions when they are compiled into the CLI. otherwise, editing a solution
+    // would re-run this script and rebuild the library for every `cargo solve`.
+    let is_registry = env::var_os("CARGO_FEATURE_REGISTRY").is_some();
+    // every solution installs its own allocator when profiling, so they can't share a binary.
+    let is_dhat = env::var_os("CARGO_FEATURE_DHAT_HEAP").is_some();
+
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let mut days: Vec<(u16, u8, String)> = vec![];
+
+    if is_registry && !is_dhat {
+        println!("cargo:rerun-if-changed=src/bin");
+
+        if let Ok(entries) = fs::read_dir(&bin_dir) {
+            for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+                let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                let Some((year, day)) = parse_stem(stem) else {
+                    continue;
+                };
+                let is_solution = fs::read_to_string(&path).is_ok_and(|s| s.contains("solution!("));
+                if is_solution {
+                    days.push((year, day, path.to_string_lossy().into_owned()));
+                }
+            }
+        }
+    }
+
+    days.sort_unstable();
+
+    let mut out = String::new();
+
+    for (year, day, path) in &days {
+        out.push_str(&format!(
+            "#[path = {path:?}]\n#[allow(dead_code)]\nmod day_{year}_{day:02};\n"
+        ));
+    }
+
+    out.push_str("\nstatic REGISTERED_DAYS: &[advent_of_code::template::registry::Entry] = &[\n");
+    for (year, day, _) in &days {
+        out.push_str(&format!(
+            "    advent_of_code::template::registry::Entry {{ year: advent_of_code::year!({year}), day: advent_of_code::day!({day}), run: day_{year}_{day:02}::__run_registered }},\n"
+        ));
+    }
+    out.push_str("];\n");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("registry.rs"), out).unwrap();
+}
+
+/// Parses the year and day of a solution file stem like `2024_01`.
+fn parse_stem(stem: &str) -> Option<(u16, u8)> {
+    let (year, day) = stem.split_once('_')?;
+    if year.len() != 4 || day.len() != 2 {
+        return None;
+    }
+    let year: u16 = year.parse().ok().filter(|year| *year >= 2015)?;
+    let day: u8 = day.parse().ok().filter(|day| (1..=25).contains(day))?;
+    Some((year, day))
+}
@@ -35,7 +35,7 @@ fn blink(stones: &HashMap<u64, u64>) -> HashMap<u64, u64> {
             0 => *new_stones.entry(1).or_default() += count,
             _ => {
                 let d = count_digits(stone);
-                if d % 2 == 0 {
+                if d.is_multiple_of(2) {
                     let (l, r) = split_digits(stone, d);
                     *new_stones.entry(l).or_default() += count;
                     *new_stones.entry(r).or_default() += count;
@@ -66,13 +66,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(55312));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(65601038650482));
     }
 }
@@ -4,6 +4,7 @@ advent_of_code::solution!(12);
 
 #[derive(Debug)]
 struct GardenPlot {
+    #[allow(dead_code)]
     farm_type: char,
     area: u32,
     perimeter: u32,
@@ -24,9 +25,9 @@ pub fn part_one(input: &str) -> Option<u32> {
     )
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(_input: &str) -> Option<u32> {
     None
-    
+
     // TODO :')
     //
     // let input = parse_input(input);
@@ -147,13 +148,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(1930));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 }
@@ -53,7 +53,7 @@ pub fn part_one(input: &str) -> Option<u32> {
     Some(calculate(&make_moves(&warehouse)))
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(_input: &str) -> Option<u32> {
     // let warehouse = parse_input(input, true);
     // Some(calculate(&make_moves(&warehouse)))
     None
@@ -135,7 +135,7 @@ fn move_obstacles(warehouse: &Warehouse, obj: (i32, i32), dir: (i32, i32)) -> Op
             warehouse.board[curr_loc.0 as usize][curr_loc.1 as usize] = prev_char;
             warehouse.board[matching_loc.0 as usize][matching_loc.1 as usize] = matching;
             warehouse.board[obj.0 as usize][obj.1 as usize] = '@';
-            warehouse.board[warehouse.player.0 as usize][warehouse.player.1 as usize] = '.';
+            warehouse.board[warehouse.player.0][warehouse.player.1] = '.';
             warehouse.player = (obj.0 as usize, obj.1 as usize);
 
             print_board(&warehouse);
@@ -143,7 +143,7 @@ fn move_obstacles(warehouse: &Warehouse, obj: (i32, i32), dir: (i32, i32)) -> Op
             let opp_dir = if dir.0 == 0 {
                 (0, dir.1 * -2)
             } else {
-                (dir.0 * -1, 0)
+                (-dir.0, 0)
             };
             curr_loc = add(curr_loc, opp_dir);
             curr_char = warehouse.board[curr_loc.0 as usize][curr_loc.1 as usize];
@@ -166,7 +166,7 @@ fn move_obstacles(warehouse: &Warehouse, obj: (i32, i32), dir: (i32, i32)) -> Op
         } else {
             warehouse.board[curr_loc.0 as usize][curr_loc.1 as usize] = 'O';
             warehouse.board[obj.0 as usize][obj.1 as usize] = '@';
-            warehouse.board[warehouse.player.0 as usize][warehouse.player.1 as usize] = '.';
+            warehouse.board[warehouse.player.0][warehouse.player.1] = '.';
             warehouse.player = (obj.0 as usize, obj.1 as usize);
         }
     }
@@ -201,13 +201,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(10092));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 }
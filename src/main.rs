@@ -1,79 +1,341 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, download, examples, read, scaffold, solve, status, time, verify,
+};
+use advent_of_code::template::run_multi::{child_commands, kill_running_children};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
+use advent_of_code::template::{Calendar, Day, Year};
 #[cfg(feature = "today")]
 use std::process;
 
+// registered solutions run inside this binary, so it installs the allocator their `solution!` macro skips.
+#[cfg(all(feature = "count-allocs", feature = "registry", not(test)))]
+#[global_allocator]
+static ALLOC: advent_of_code::template::memory::CountingAlloc =
+    advent_of_code::template::memory::CountingAlloc;
+
+// solutions compiled into this binary, see `build.rs`.
+#[cfg(all(feature = "registry", not(test)))]
+mod registered {
+    include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+
+    pub fn install() {
+        advent_of_code::template::registry::register(REGISTERED_DAYS);
+    }
+}
+
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::bench::{parse_duration, BenchConfig};
+    use advent_of_code::template::commands::scaffold;
+    use advent_of_code::template::commands::time::Checks;
+    use advent_of_code::template::input::{InputError, InputSource, EXAMPLE_FLAG, INPUT_FLAG};
+    use advent_of_code::template::protocol::JSON_FLAG;
+    use advent_of_code::template::regressions::{parse_threshold, DEFAULT_THRESHOLD};
+    use advent_of_code::template::run_multi::child_commands::REGISTERED_COMMAND;
+    use advent_of_code::template::run_multi::Timeouts;
+    use advent_of_code::template::{Calendar, Day, Year};
+    use std::path::PathBuf;
     use std::process;
 
     pub enum AppArguments {
         Download {
+            year: Year,
             day: Day,
         },
         Read {
+            year: Year,
+            day: Day,
+        },
+        Examples {
+            year: Year,
             day: Day,
         },
         Scaffold {
+            year: Year,
             day: Day,
             download: bool,
-            overwrite: bool,
+            options: scaffold::Options,
         },
+        ScaffoldTemplates,
         Solve {
+            year: Year,
             day: Day,
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            watch: bool,
+            test: bool,
+            input: InputSource,
         },
         All {
+            year: Year,
             release: bool,
+            jobs: usize,
+            timeouts: Timeouts,
         },
         Time {
+            year: Year,
             all: bool,
             day: Option<Day>,
             store: bool,
+            memory: bool,
+            report: Option<PathBuf>,
+            bench: BenchConfig,
+            checks: Checks,
+            jobs: usize,
+            timeouts: Timeouts,
+        },
+        TimeHistory {
+            year: Year,
+            day: Day,
+        },
+        Verify {
+            year: Year,
+            release: bool,
+        },
+        Status {
+            year: Year,
+            json: bool,
+        },
+        RunRegistered {
+            year: Year,
+            day: Day,
+            bench: Option<BenchConfig>,
+            input: InputSource,
         },
         #[cfg(feature = "today")]
         Today,
     }
 
+    /// Parses the `--input` and `--example` flags of a command that runs a solution.
+    /// The number of an example is optional, so it has to be the last free argument.
+    fn parse_input(
+        args: &mut pico_args::Arguments,
+    ) -> Result<InputSource, Box<dyn std::error::Error>> {
+        let input_path = args.opt_value_from_os_str(INPUT_FLAG, |s| {
+            Ok::<_, std::convert::Infallible>(PathBuf::from(s))
+        })?;
+        let example = args.contains(EXAMPLE_FLAG);
+        let example_number = if example {
+            args.opt_free_from_str::<u8>()?
+        } else {
+            None
+        };
+
+        Ok(match (input_path, example) {
+            (Some(_), true) => return Err(InputError::Conflicting.into()),
+            (Some(path), false) if path.as_os_str() == "-" => InputSource::Stdin,
+            (Some(path), false) => InputSource::File(path),
+            (None, true) => InputSource::Example(example_number),
+            (None, false) => InputSource::Puzzle,
+        })
+    }
+
+    /// Checks that the event of `year` has a puzzle on `day`.
+    fn event_day(year: Year, day: Day) -> Result<Day, String> {
+        let calendar = Calendar::of(year);
+        if calendar.contains(day) {
+            Ok(day)
+        } else {
+            Err(format!(
+                "the {year} event only has {} days, there is no day {day}.",
+                calendar.days
+            ))
+        }
+    }
+
+    fn parse_timeouts(
+        args: &mut pico_args::Arguments,
+    ) -> Result<Timeouts, Box<dyn std::error::Error>> {
+        let mut parse = |key| {
+            args.opt_value_from_fn(key, |s| {
+                parse_duration(s).ok_or("expecting a duration like `500ms` or `2s`")
+            })
+        };
+        Ok(Timeouts {
+            part: parse("--timeout")?,
+            day: parse("--day-timeout")?,
+        })
+    }
+
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
 
-        let app_args = match args.subcommand()?.as_deref() {
+        let subcommand = args.subcommand()?;
+
+        // every command works on a single year, defaulting to `AOC_YEAR`.
+        let year_arg: Option<Year> = args.opt_value_from_str("--year")?;
+        let year = || {
+            year_arg
+                .or_else(Year::from_env)
+                .ok_or("no year given. Pass `--year` or set `AOC_YEAR` in `.cargo/config.toml`.")
+        };
+
+        let app_args = match subcommand.as_deref() {
             Some("all") => AppArguments::All {
+                year: year()?,
                 release: args.contains("--release"),
+                jobs: args.opt_value_from_str("--jobs")?.unwrap_or(1),
+                timeouts: parse_timeouts(&mut args)?,
             },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let memory = args.contains("--memory");
+                let report = args.opt_value_from_os_str("--report", |s| {
+                    Ok::<_, std::convert::Infallible>(PathBuf::from(s))
+                })?;
+
+                let mut bench = BenchConfig::from_env();
+                if let Some(budget) = args.opt_value_from_fn("--bench-time", |s| {
+                    parse_duration(s).ok_or("expecting a duration like `500ms` or `2s`")
+                })? {
+                    bench.budget = budget;
+                }
+                if let Some(min_samples) = args.opt_value_from_str("--min-samples")? {
+                    bench.min_samples = u128::max(min_samples, 1);
+                }
+                if let Some(max_samples) = args.opt_value_from_str("--max-samples")? {
+                    bench.max_samples = max_samples;
+                }
+                if let Some(warmup) = args.opt_value_from_str("--warmup")? {
+                    bench.warmup = warmup;
+                }
+                bench.max_samples = bench.max_samples.max(bench.min_samples);
 
-                AppArguments::Time {
-                    all,
-                    day: args.opt_free_from_str()?,
-                    store,
+                let jobs = args.opt_value_from_str("--jobs")?.unwrap_or(1);
+                let timeouts = parse_timeouts(&mut args)?;
+
+                let check = args.contains("--check");
+                let threshold = args
+                    .opt_value_from_fn("--threshold", |s| {
+                        parse_threshold(s).ok_or("expecting a percentage like `10` or `10%`")
+                    })?
+                    .unwrap_or(DEFAULT_THRESHOLD);
+                let checks = Checks {
+                    threshold: check.then_some(threshold),
+                    budget: args.opt_value_from_fn("--budget", |s| {
+                        parse_duration(s).ok_or("expecting a duration like `500ms` or `2s`")
+                    })?,
+                };
+
+                // `cargo time history <day>` shares the subcommand with `cargo time [<day>]`.
+                match args.opt_free_from_str::<String>()?.as_deref() {
+                    Some("history") => AppArguments::TimeHistory {
+                        year: year()?,
+                        day: event_day(year()?, args.free_from_str()?)?,
+                    },
+                    Some(day) => AppArguments::Time {
+                        year: year()?,
+                        all,
+                        day: Some(event_day(year()?, day.parse()?)?),
+                        store,
+                        memory,
+                        report: report.clone(),
+                        bench,
+                        checks,
+                        jobs,
+                        timeouts,
+                    },
+                    None => AppArguments::Time {
+                        year: year()?,
+                        all,
+                        day: None,
+                        store,
+                        memory,
+                        report: report.clone(),
+                        bench,
+                        checks,
+                        jobs,
+                        timeouts,
+                    },
                 }
             }
+            Some("verify") => AppArguments::Verify {
+                year: year()?,
+                release: args.contains("--release"),
+            },
+            Some("status") => AppArguments::Status {
+                year: year()?,
+                json: args.contains(JSON_FLAG),
+            },
             Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
+                year: year()?,
+                day: event_day(year()?, args.free_from_str()?)?,
             },
             Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
+                year: year()?,
+                day: event_day(year()?, args.free_from_str()?)?,
             },
-            Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
-                download: args.contains("--download"),
-                overwrite: args.contains("--overwrite"),
-            },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
+            Some("examples") => AppArguments::Examples {
+                year: year()?,
+                day: event_day(year()?, args.free_from_str()?)?,
             },
+            Some("scaffold") => {
+                let download = args.contains("--download");
+                let options = scaffold::Options {
+                    template: args
+                        .opt_value_from_str("--template")?
+                        .unwrap_or_else(|| scaffold::DEFAULT_TEMPLATE.into()),
+                    answer_type: args
+                        .opt_value_from_str("--answer-type")?
+                        .unwrap_or_else(|| scaffold::DEFAULT_ANSWER_TYPE.into()),
+                    overwrite: args.contains("--overwrite"),
+                    dry_run: args.contains("--dry-run"),
+                };
+
+                // `cargo scaffold templates` shares the subcommand with `cargo scaffold <day>`.
+                match args.free_from_str::<String>()?.as_str() {
+                    "templates" => AppArguments::ScaffoldTemplates,
+                    day => AppArguments::Scaffold {
+                        year: year()?,
+                        day: event_day(year()?, day.parse()?)?,
+                        download,
+                        options,
+                    },
+                }
+            }
+            Some("solve") => {
+                let day = event_day(year()?, args.free_from_str()?)?;
+                let release = args.contains("--release");
+                let submit = args.opt_value_from_str("--submit")?;
+                let dhat = args.contains("--dhat");
+                let watch = args.contains("--watch");
+                let test = args.contains("--test");
+
+                let input = parse_input(&mut args)?;
+
+                if submit.is_some() && input != InputSource::Puzzle {
+                    return Err(
+                        format!("refusing to submit an answer computed from {input}.").into(),
+                    );
+                }
+                if watch && input == InputSource::Stdin {
+                    return Err("stdin can only be read once, watch a file instead.".into());
+                }
+
+                AppArguments::Solve {
+                    year: year()?,
+                    day,
+                    release,
+                    dhat,
+                    submit,
+                    watch,
+                    test,
+                    input,
+                }
+            }
+            Some(REGISTERED_COMMAND) => {
+                // reports are always printed as JSON lines.
+                args.contains(JSON_FLAG);
+                AppArguments::RunRegistered {
+                    bench: args.contains("--time").then(BenchConfig::from_env),
+                    year: args.free_from_str()?,
+                    day: args.free_from_str()?,
+                    input: parse_input(&mut args)?,
+                }
+            }
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
             Some(x) => {
@@ -96,44 +358,107 @@ mod args {
 }
 
 fn main() {
+    #[cfg(all(feature = "registry", not(test)))]
+    registered::install();
+
+    // don't leave spawned solutions running when the runner is interrupted.
+    let _ = ctrlc::set_handler(|| {
+        kill_running_children();
+        std::process::exit(130);
+    });
+
     match parse() {
         Err(err) => {
             eprintln!("Error: {err}");
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
+            AppArguments::All {
+                year,
+                release,
+                jobs,
+                timeouts,
+            } => all::handle(year, release, jobs, &timeouts),
+            AppArguments::Time {
+                year,
+                day,
+                all,
+                store,
+                memory,
+                report,
+                bench,
+                checks,
+                jobs,
+                timeouts,
+            } => time::handle(
+                year,
+                day,
+                all,
+                store,
+                memory,
+                report.as_deref(),
+                &bench,
+                &checks,
+                jobs,
+                &timeouts,
+            ),
+            AppArguments::TimeHistory { year, day } => time::history(year, day),
+            AppArguments::Verify { year, release } => verify::handle(year, release),
+            AppArguments::Status { year, json } => status::handle(year, json),
+            AppArguments::RunRegistered {
+                year,
+                day,
+                bench,
+                input,
+            } => {
+                child_commands::print_registered(year, day, bench.as_ref(), &input);
+            }
+            AppArguments::ScaffoldTemplates => scaffold::list_templates(),
+            AppArguments::Download { year, day } => download::handle(year, day),
+            AppArguments::Read { year, day } => read::handle(year, day),
+            AppArguments::Examples { year, day } => examples::handle(year, day),
             AppArguments::Scaffold {
+                year,
                 day,
                 download,
-                overwrite,
+                options,
             } => {
-                scaffold::handle(day, overwrite);
-                if download {
-                    download::handle(day);
+                scaffold::handle(year, day, &options);
+                if download && !options.dry_run {
+                    download::handle(year, day);
                 }
             }
             AppArguments::Solve {
+                year,
                 day,
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                watch,
+                test,
+                input,
+            } => {
+                if watch {
+                    solve::watch(year, day, release, test, &input);
+                } else if test {
+                    solve::test(year, day, release);
+                } else {
+                    solve::handle(year, day, release, dhat, submit, &input);
+                }
+            }
             #[cfg(feature = "today")]
             AppArguments::Today => {
-                match Day::today() {
-                    Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
-                        read::handle(day)
+                match Year::today().zip(Day::today()) {
+                    Some((year, day)) => {
+                        scaffold::handle(year, day, &scaffold::Options::default());
+                        download::handle(year, day);
+                        read::handle(year, day)
                     }
                     None => {
+                        let days = Year::today().map_or(25, |year| Calendar::of(year).days);
                         eprintln!(
                             "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
+                            the {days}th of december. Please use `scaffold` with a specific day."
                         );
                         process::exit(1)
                     }
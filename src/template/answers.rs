@@ -0,0 +1,297 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Error, ErrorKind},
+    path::Path,
+    str::FromStr,
+};
+use tinyjson::JsonValue;
+
+use crate::template::{data_dir, Day, Year};
+
+/// Name of the file inside the data directory of a year.
+static ANSWERS_FILE_NAME: &str = "answers.json";
+
+/// Integers from 2^53 on can't all be represented by the `f64` that JSON numbers are read as.
+const MAX_EXACT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// Represents the accepted answers for a single day.
+#[derive(Clone, Debug)]
+pub struct Answer {
+    pub day: Day,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+impl Answer {
+    pub fn part(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_1.as_deref(),
+            2 => self.part_2.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Represents the accepted answers for a set of days.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Answers {
+    pub data: Vec<Answer>,
+}
+
+impl Answers {
+    /// Dehydrate answers to the JSON file of a year.
+    pub fn store_file(&self, year: Year) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        fs::create_dir_all(data_dir(year))?;
+        let mut file = fs::File::create(data_dir(year).join(ANSWERS_FILE_NAME))?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate answers from the JSON file of a year. If not present, returns empty answers.
+    /// A file that can't be read or parsed is an error, so that it is never overwritten.
+    pub fn read_from_file(year: Year) -> Result<Self, String> {
+        Self::read_from_path(&data_dir(year).join(ANSWERS_FILE_NAME))
+    }
+
+    fn read_from_path(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(json) => Answers::try_from(json),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.to_string()),
+        }
+        .map_err(|e| format!("could not read {}: {e}", path.display()))
+    }
+
+    pub fn get(&self, day: Day) -> Option<&Answer> {
+        self.data.iter().find(|a| a.day == day)
+    }
+
+    /// Store the accepted answer for a part, overwriting a previous one.
+    pub fn set(&mut self, day: Day, part: u8, answer: &str) {
+        let index = match self.data.iter().position(|a| a.day == day) {
+            Some(index) => index,
+            None => {
+                self.data.push(Answer {
+                    day,
+                    part_1: None,
+                    part_2: None,
+                });
+                self.data.sort_unstable_by_key(|a| a.day);
+                self.data.iter().position(|a| a.day == day).unwrap()
+            }
+        };
+
+        let entry = &mut self.data[index];
+        match part {
+            1 => entry.part_1 = Some(answer.into()),
+            2 => entry.part_2 = Some(answer.into()),
+            _ => {}
+        }
+    }
+
+    /// Days with at least one accepted answer.
+    pub fn solved_days(&self) -> impl Iterator<Item = Day> + '_ {
+        self.data
+            .iter()
+            .filter(|a| a.part_1.is_some() || a.part_2.is_some())
+            .map(|a| a.day)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Answers> for JsonValue {
+    fn from(value: Answers) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Answers {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Answers {
+            data: json_data
+                .iter()
+                .map(Answer::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Answer> for JsonValue {
+    fn from(value: &Answer) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+
+        map.insert(
+            "part_1".into(),
+            value
+                .part_1
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+
+        map.insert(
+            "part_2".into(),
+            value
+                .part_2
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Answer {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected answer to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected answer.day to be a Day struct.")?;
+
+        // answers may be added by hand, so accept numbers as well as strings.
+        let parse_part = |key: &str| -> Result<Option<String>, String> {
+            match json.get(key) {
+                None | Some(JsonValue::Null) => Ok(None),
+                Some(JsonValue::String(s)) => Ok(Some(s.clone())),
+                Some(JsonValue::Number(n)) if n.fract() == 0.0 && n.abs() < MAX_EXACT_INTEGER => {
+                    Ok(Some(n.to_string()))
+                }
+                Some(JsonValue::Number(_)) => Err(format!(
+                    "Expected answer.{key} to be an integer below 2^53, store larger answers as strings."
+                )),
+                Some(_) => Err(format!(
+                    "Expected answer.{key} to be null, string or number."
+                )),
+            }
+        };
+
+        Ok(Answer {
+            day,
+            part_1: parse_part("part_1")?,
+            part_2: parse_part("part_2")?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{env, fs};
+
+    use crate::day;
+
+    use super::Answers;
+
+    #[test]
+    fn handles_hand_written_answers() {
+        let json =
+            r#"{ "data": [{ "day": "01", "part_1": 11, "part_2": "31" }, { "day": "02" }] }"#
+                .to_string();
+        let answers = Answers::try_from(json).unwrap();
+        assert_eq!(answers.data.len(), 2);
+        assert_eq!(answers.get(day!(1)).unwrap().part(1), Some("11"));
+        assert_eq!(answers.get(day!(1)).unwrap().part(2), Some("31"));
+        assert_eq!(answers.get(day!(2)).unwrap().part(1), None);
+        assert_eq!(answers.solved_days().collect::<Vec<_>>(), vec![day!(1)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_answers() {
+        let json = r#"{ "data": [{ "day": "01", "part_1": [] }] }"#.to_string();
+        Answers::try_from(json).unwrap();
+    }
+
+    #[test]
+    fn rejects_inexact_numbers() {
+        let json = r#"{ "data": [{ "day": "01", "part_1": 9007199254740991 }] }"#.to_string();
+        let answers = Answers::try_from(json).unwrap();
+        assert_eq!(
+            answers.get(day!(1)).unwrap().part(1),
+            Some("9007199254740991")
+        );
+
+        for number in ["9007199254740993", "1.5"] {
+            let json = format!(r#"{{ "data": [{{ "day": "01", "part_1": {number} }}] }}"#);
+            assert!(Answers::try_from(json).is_err());
+        }
+    }
+
+    #[test]
+    fn only_treats_missing_files_as_empty() {
+        let dir = env::temp_dir().join(format!("aoc-answers-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("answers.json");
+
+        let missing = Answers::read_from_path(&path);
+        fs::write(&path, r#"{ "data": [{ "day": "01", "part_1": 1e20 }] }"#).unwrap();
+        let invalid = Answers::read_from_path(&path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(missing.unwrap().data.is_empty());
+        assert!(invalid
+            .unwrap_err()
+            .contains("store larger answers as strings"));
+    }
+
+    #[test]
+    fn sets_answers_in_day_order() {
+        let mut answers = Answers::default();
+        answers.set(day!(3), 1, "7");
+        answers.set(day!(1), 2, "5");
+        answers.set(day!(3), 2, "8");
+        answers.set(day!(3), 1, "9");
+
+        assert_eq!(answers.data.len(), 2);
+        assert_eq!(answers.data[0].day, day!(1));
+        assert_eq!(answers.data[0].part(1), None);
+        assert_eq!(answers.data[0].part(2), Some("5"));
+        assert_eq!(answers.data[1].part(1), Some("9"));
+        assert_eq!(answers.data[1].part(2), Some("8"));
+    }
+
+    #[test]
+    fn round_trips_answers() {
+        let mut answers = Answers::default();
+        answers.set(day!(4), 1, "18");
+
+        let json = tinyjson::JsonValue::from(answers).stringify().unwrap();
+        let parsed = Answers::try_from(json).unwrap();
+
+        assert_eq!(parsed.get(day!(4)).unwrap().part(1), Some("18"));
+        assert_eq!(parsed.get(day!(4)).unwrap().part(2), None);
+    }
+}
@@ -0,0 +1,475 @@
+/// Native client for the adventofcode.com website.
+use std::path::{Path, PathBuf};
+use std::{env, fmt::Display, fs, io, time::Duration};
+
+use regex::Regex;
+
+use crate::template::{data_dir, html_markdown, Day, Year};
+
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = "github.com/fspoettel/advent-of-code-rust";
+const SESSION_FILE_NAME: &str = "adventofcode.session";
+
+#[derive(Debug)]
+pub enum AocCommandError {
+    /// No session cookie was found in the environment or in a config file.
+    SessionNotFound,
+    /// The server rejected the session cookie.
+    Unauthorized,
+    /// The server asked us to wait before trying again.
+    RateLimited(Option<Duration>),
+    /// The server responded with an unexpected status code.
+    HttpStatus {
+        status: u16,
+        url: String,
+    },
+    /// The request could not be sent or its response could not be read.
+    Transport(String),
+    /// A response did not have the expected shape.
+    UnexpectedResponse(String),
+    IO(io::Error),
+}
+
+impl Display for AocCommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocCommandError::SessionNotFound => write!(
+                f,
+                "no session cookie found. Set `AOC_SESSION` or create \"~/.{SESSION_FILE_NAME}\"."
+            ),
+            AocCommandError::Unauthorized => {
+                write!(f, "the session cookie was rejected. It might have expired.")
+            }
+            AocCommandError::RateLimited(Some(wait)) => {
+                write!(f, "rate limited, retry in {}s.", wait.as_secs())
+            }
+            AocCommandError::RateLimited(None) => write!(f, "rate limited, retry later."),
+            AocCommandError::HttpStatus { status, url } => {
+                write!(f, "request to {url} failed with status {status}.")
+            }
+            AocCommandError::Transport(e) => write!(f, "request failed: {e}"),
+            AocCommandError::UnexpectedResponse(e) => write!(f, "unexpected response: {e}"),
+            AocCommandError::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for AocCommandError {}
+
+impl From<io::Error> for AocCommandError {
+    fn from(e: io::Error) -> Self {
+        AocCommandError::IO(e)
+    }
+}
+
+/// A client bound to a session and an event year.
+pub struct AocClient {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+    year: Year,
+}
+
+impl AocClient {
+    pub fn new(base_url: &str, session: &str, year: Year) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(USER_AGENT)
+            // unauthenticated requests are redirected, surface them instead of following.
+            .redirects(0)
+            .timeout(Duration::from_secs(30))
+            .build();
+
+        Self {
+            agent,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            year,
+        }
+    }
+
+    /// Creates a client for `year` from the environment:
+    ///  1. the base url is read from `AOC_BASE_URL`, defaulting to adventofcode.com.
+    ///  2. the session is read from `AOC_SESSION`, `~/.adventofcode.session` or `~/.config/adventofcode.session`.
+    pub fn from_env(year: Year) -> Result<Self, AocCommandError> {
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.into());
+        let session = read_session()?;
+        Ok(Self::new(&base_url, &session, year))
+    }
+
+    /// Fetches the puzzle input for a day.
+    pub fn get_input(&self, day: Day) -> Result<String, AocCommandError> {
+        self.get(&format!("/{}/day/{}/input", self.year, day.into_inner()))
+    }
+
+    /// Fetches the puzzle description for a day and converts it to markdown.
+    pub fn get_puzzle(&self, day: Day) -> Result<String, AocCommandError> {
+        let html = self.get(&format!("/{}/day/{}", self.year, day.into_inner()))?;
+        let markdown = html_markdown::articles_to_markdown(&html);
+
+        if markdown.is_empty() {
+            return Err(AocCommandError::UnexpectedResponse(
+                "puzzle page does not contain a description.".into(),
+            ));
+        }
+
+        Ok(markdown + "\n")
+    }
+
+    /// Posts an answer and returns the message the server responded with.
+    pub fn submit(&self, day: Day, part: u8, answer: &str) -> Result<String, AocCommandError> {
+        let path = format!("/{}/day/{}/answer", self.year, day.into_inner());
+        let level = part.to_string();
+
+        let html = self.send(
+            &path,
+            self.request("POST", &path)
+                .send_form(&[("level", &level), ("answer", answer)]),
+        )?;
+
+        let message = html_markdown::articles_to_markdown(&html);
+
+        if message.is_empty() {
+            return Err(AocCommandError::UnexpectedResponse(
+                "submission response does not contain a message.".into(),
+            ));
+        }
+
+        if message.contains("You gave an answer too recently") {
+            return Err(AocCommandError::RateLimited(parse_wait_time(&message)));
+        }
+
+        Ok(message)
+    }
+
+    /// Downloads input and puzzle description for a day into `data/<year>/`.
+    pub fn download(&self, day: Day) -> Result<(), AocCommandError> {
+        let input_path = get_input_path(self.year, day);
+        let puzzle_path = get_puzzle_path(self.year, day);
+
+        let input = self.get_input(day)?;
+        let puzzle = self.get_puzzle(day)?;
+
+        write_file(&input_path, &input)?;
+        println!(
+            "🎄 Successfully wrote input to \"{}\".",
+            input_path.display()
+        );
+        write_file(&puzzle_path, &puzzle)?;
+        println!(
+            "🎄 Successfully wrote puzzle to \"{}\".",
+            puzzle_path.display()
+        );
+
+        Ok(())
+    }
+
+    /// Fetches the puzzle description for a day, stores it in `data/<year>/puzzles` and returns it.
+    pub fn read(&self, day: Day) -> Result<String, AocCommandError> {
+        let puzzle = self.get_puzzle(day)?;
+        write_file(&get_puzzle_path(self.year, day), &puzzle)?;
+        Ok(puzzle)
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        self.agent
+            .request(method, &format!("{}{path}", self.base_url))
+            .set("Cookie", &format!("session={}", self.session))
+    }
+
+    fn get(&self, path: &str) -> Result<String, AocCommandError> {
+        self.send(path, self.request("GET", path).call())
+    }
+
+    fn send(
+        &self,
+        path: &str,
+        result: Result<ureq::Response, ureq::Error>,
+    ) -> Result<String, AocCommandError> {
+        let url = format!("{}{path}", self.base_url);
+
+        let response = match result {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => {
+                return Err(status_error(status, &response, url));
+            }
+            Err(ureq::Error::Transport(e)) => {
+                return Err(AocCommandError::Transport(e.to_string()))
+            }
+        };
+
+        match response.status() {
+            200..=299 => response
+                .into_string()
+                .map_err(|e| AocCommandError::Transport(e.to_string())),
+            // adventofcode.com redirects requests without a valid session.
+            300..=399 => Err(AocCommandError::Unauthorized),
+            status => Err(AocCommandError::HttpStatus { status, url }),
+        }
+    }
+}
+
+fn status_error(status: u16, response: &ureq::Response, url: String) -> AocCommandError {
+    match status {
+        // the input endpoint answers with a 400 when the session is missing or invalid.
+        400 | 401 | 403 => AocCommandError::Unauthorized,
+        429 => AocCommandError::RateLimited(
+            response
+                .header("Retry-After")
+                .and_then(|s| s.trim().parse().ok())
+                .map(Duration::from_secs),
+        ),
+        _ => AocCommandError::HttpStatus { status, url },
+    }
+}
+
+/// Parses wait times like `You have 1m 5s left to wait.` from a submission response.
+fn parse_wait_time(message: &str) -> Option<Duration> {
+    let re = Regex::new(r"(?:(\d+)m\s*)?(\d+)s left to wait").unwrap();
+    let caps = re.captures(message)?;
+
+    let minutes: u64 = caps.get(1).map_or(Some(0), |m| m.as_str().parse().ok())?;
+    let seconds: u64 = caps.get(2)?.as_str().parse().ok()?;
+
+    Some(Duration::from_secs(minutes * 60 + seconds))
+}
+
+fn read_session() -> Result<String, AocCommandError> {
+    if let Ok(session) = env::var("AOC_SESSION") {
+        if !session.trim().is_empty() {
+            return Ok(session.trim().to_string());
+        }
+    }
+
+    read_session_file(&session_file_candidates()).ok_or(AocCommandError::SessionNotFound)
+}
+
+/// Reads the first of the session files that exists and is not empty.
+fn read_session_file(candidates: &[PathBuf]) -> Option<String> {
+    candidates.iter().find_map(|path| {
+        let session = fs::read_to_string(path).ok()?;
+        let session = session.trim();
+        (!session.is_empty()).then(|| session.to_string())
+    })
+}
+
+fn session_file_candidates() -> Vec<PathBuf> {
+    let mut candidates = vec![];
+
+    let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"));
+
+    if let Some(home) = &home {
+        candidates.push(PathBuf::from(home).join(format!(".{SESSION_FILE_NAME}")));
+    }
+
+    if let Some(config) = env::var_os("XDG_CONFIG_HOME") {
+        candidates.push(PathBuf::from(config).join(SESSION_FILE_NAME));
+    } else if let Some(home) = &home {
+        candidates.push(PathBuf::from(home).join(".config").join(SESSION_FILE_NAME));
+    }
+
+    candidates
+}
+
+fn get_input_path(year: Year, day: Day) -> PathBuf {
+    data_dir(year).join("inputs").join(format!("{day}.txt"))
+}
+
+pub(crate) fn get_puzzle_path(year: Year, day: Day) -> PathBuf {
+    data_dir(year).join("puzzles").join(format!("{day}.md"))
+}
+
+/// Writes a file, creating the data directories of a new year on the way.
+fn write_file(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{
+        env, fs,
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+        time::Duration,
+    };
+
+    use super::{parse_wait_time, read_session_file, AocClient, AocCommandError};
+    use crate::{day, year};
+
+    /// A captured request made against the stand-in server.
+    struct Request {
+        head: String,
+        body: String,
+    }
+
+    /// Formats a raw HTTP response.
+    fn response(status: &str, headers: &[&str], body: &str) -> String {
+        let mut lines = vec![format!("HTTP/1.1 {status}")];
+        lines.extend(headers.iter().map(|h| h.to_string()));
+        lines.push(format!("Content-Length: {}", body.len()));
+        lines.push("Connection: close".into());
+        format!("{}\r\n\r\n{body}", lines.join("\r\n"))
+    }
+
+    /// Starts a local server that answers a single request with `response`.
+    fn serve_once(response: String) -> (String, mpsc::Receiver<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut head = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(len) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = len.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let mut stream = reader.into_inner();
+            stream.write_all(response.as_bytes()).unwrap();
+
+            tx.send(Request {
+                head,
+                body: String::from_utf8(body).unwrap(),
+            })
+            .unwrap();
+        });
+
+        (base_url, rx)
+    }
+
+    #[test]
+    fn fetches_input_with_session_cookie() {
+        let (base_url, rx) = serve_once(response("200 OK", &[], "3   4\n1\n"));
+        let client = AocClient::new(&base_url, "abc", year!(2024));
+
+        let input = client.get_input(day!(1)).unwrap();
+        let request = rx.recv().unwrap();
+
+        assert_eq!(input, "3   4\n1\n");
+        assert!(request.head.starts_with("GET /2024/day/1/input "));
+        assert!(request.head.contains("session=abc"));
+    }
+
+    #[test]
+    fn converts_puzzle_to_markdown() {
+        let (base_url, _rx) = serve_once(response(
+            "200 OK",
+            &[],
+            "<main><article><h2>--- Day 2 ---</h2><p>Some <em>text</em>.</p></article>",
+        ));
+        let client = AocClient::new(&base_url, "abc", year!(2024));
+
+        let puzzle = client.get_puzzle(day!(2)).unwrap();
+        assert_eq!(puzzle, "## --- Day 2 ---\n\nSome *text*.\n");
+    }
+
+    #[test]
+    fn posts_answers() {
+        let (base_url, rx) = serve_once(response(
+            "200 OK",
+            &[],
+            "<article><p>That's the right answer!</p></article>",
+        ));
+        let client = AocClient::new(&base_url, "abc", year!(2024));
+
+        let message = client.submit(day!(3), 2, "42").unwrap();
+        let request = rx.recv().unwrap();
+
+        assert_eq!(message, "That's the right answer!");
+        assert!(request.head.starts_with("POST /2024/day/3/answer "));
+        assert_eq!(request.body, "level=2&answer=42");
+    }
+
+    #[test]
+    fn reports_rate_limited_submissions() {
+        let (base_url, _rx) = serve_once(response(
+            "200 OK",
+            &[],
+            "<article><p>You gave an answer too recently. You have 1m 5s left to wait.</p></article>",
+        ));
+        let client = AocClient::new(&base_url, "abc", year!(2024));
+
+        let result = client.submit(day!(3), 1, "42");
+        assert!(matches!(
+            result,
+            Err(AocCommandError::RateLimited(Some(d))) if d == Duration::from_secs(65)
+        ));
+    }
+
+    #[test]
+    fn reports_rejected_sessions() {
+        let (base_url, _rx) = serve_once(response("400 Bad Request", &[], ""));
+        let client = AocClient::new(&base_url, "expired", year!(2024));
+
+        let result = client.get_input(day!(1));
+        assert!(matches!(result, Err(AocCommandError::Unauthorized)));
+    }
+
+    #[test]
+    fn reports_redirects_as_unauthorized() {
+        let (base_url, _rx) = serve_once(response("302 Found", &["Location: /2024/day/1"], ""));
+        let client = AocClient::new(&base_url, "", year!(2024));
+
+        let result = client.submit(day!(1), 1, "1");
+        assert!(matches!(result, Err(AocCommandError::Unauthorized)));
+    }
+
+    #[test]
+    fn reports_throttled_requests() {
+        let (base_url, _rx) =
+            serve_once(response("429 Too Many Requests", &["Retry-After: 30"], ""));
+        let client = AocClient::new(&base_url, "abc", year!(2024));
+
+        let result = client.get_input(day!(1));
+        assert!(matches!(
+            result,
+            Err(AocCommandError::RateLimited(Some(d))) if d == Duration::from_secs(30)
+        ));
+    }
+
+    #[test]
+    fn parses_wait_times() {
+        assert_eq!(
+            parse_wait_time("You have 37s left to wait."),
+            Some(Duration::from_secs(37))
+        );
+        assert_eq!(
+            parse_wait_time("You have 4m 2s left to wait."),
+            Some(Duration::from_secs(242))
+        );
+        assert_eq!(parse_wait_time("Please wait."), None);
+    }
+
+    #[test]
+    fn skips_empty_session_files() {
+        let dir = env::temp_dir().join(format!("aoc-session-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (empty, valid) = (dir.join("empty"), dir.join("valid"));
+        fs::write(&empty, " \n").unwrap();
+        fs::write(&valid, "abc123\n").unwrap();
+
+        let session = read_session_file(&[dir.join("missing"), empty, valid]);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(session, Some("abc123".into()));
+    }
+}
@@ -0,0 +1,323 @@
+/// Benchmark configuration and summary statistics.
+use std::{collections::HashMap, env, time::Duration};
+use tinyjson::JsonValue;
+
+const BENCH_TIME_ENV: &str = "AOC_BENCH_TIME";
+const MIN_SAMPLES_ENV: &str = "AOC_BENCH_MIN_SAMPLES";
+const MAX_SAMPLES_ENV: &str = "AOC_BENCH_MAX_SAMPLES";
+const WARMUP_ENV: &str = "AOC_BENCH_WARMUP";
+
+/// Controls how long and how often a part is benched.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Approximate time spent sampling a single part.
+    pub budget: Duration,
+    pub min_samples: u128,
+    pub max_samples: u128,
+    /// Number of untimed runs before sampling starts.
+    pub warmup: u32,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            budget: Duration::from_secs(1),
+            min_samples: 10,
+            max_samples: 10_000,
+            warmup: 3,
+        }
+    }
+}
+
+impl BenchConfig {
+    /// Reads the configuration from `AOC_BENCH_*` environment variables, falling back to defaults.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+
+        let budget = env::var(BENCH_TIME_ENV)
+            .ok()
+            .and_then(|s| parse_duration(&s))
+            .unwrap_or(default.budget);
+
+        let read_number = |key: &str| env::var(key).ok().and_then(|s| s.trim().parse().ok());
+
+        // every benched part needs at least one sample to compute stats from.
+        let min_samples = read_number(MIN_SAMPLES_ENV)
+            .unwrap_or(default.min_samples)
+            .max(1);
+        let max_samples = read_number(MAX_SAMPLES_ENV)
+            .unwrap_or(default.max_samples)
+            .max(min_samples);
+
+        let warmup = env::var(WARMUP_ENV)
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(default.warmup);
+
+        Self {
+            budget,
+            min_samples,
+            max_samples,
+            warmup,
+        }
+    }
+
+    /// Environment variables that forward this configuration to a solution binary.
+    pub fn to_env(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (BENCH_TIME_ENV, format!("{}ns", self.budget.as_nanos())),
+            (MIN_SAMPLES_ENV, self.min_samples.to_string()),
+            (MAX_SAMPLES_ENV, self.max_samples.to_string()),
+            (WARMUP_ENV, self.warmup.to_string()),
+        ]
+    }
+
+    /// Number of samples to take for a part that ran once in `base_time`.
+    pub fn sample_count(&self, base_time: &Duration) -> u128 {
+        (self.budget.as_nanos() / base_time.as_nanos().max(10))
+            .clamp(self.min_samples, self.max_samples)
+    }
+}
+
+/// Parses durations like `500ms`, `1.5s` or `2m`.
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let unit_start = s.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (value, unit) = s.split_at(unit_start);
+    let value: f64 = value.parse().ok()?;
+
+    let nanos_per_unit = match unit.trim() {
+        "ns" => 1.0,
+        "us" | "µs" => 1e3,
+        "ms" => 1e6,
+        "s" => 1e9,
+        "m" => 60e9,
+        _ => return None,
+    };
+
+    if !value.is_finite() || value < 0.0 {
+        return None;
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    Some(Duration::from_nanos((value * nanos_per_unit) as u64))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Summary of a set of benchmark samples. All times are in nanoseconds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchStats {
+    pub samples: u128,
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub p95: f64,
+    pub max: f64,
+    pub std_dev: f64,
+    /// Samples outside of 1.5 times the interquartile range.
+    pub outliers: u128,
+}
+
+impl BenchStats {
+    /// Computes statistics for a non-empty set of samples.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut nanos: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        nanos.sort_unstable_by(f64::total_cmp);
+
+        let n = nanos.len() as f64;
+        let mean = nanos.iter().sum::<f64>() / n;
+
+        let std_dev = if nanos.len() > 1 {
+            (nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+
+        let q1 = quantile(&nanos, 0.25);
+        let q3 = quantile(&nanos, 0.75);
+        let iqr = q3 - q1;
+        let (low, high) = (q1 - 1.5 * iqr, q3 + 1.5 * iqr);
+
+        Some(Self {
+            samples: nanos.len() as u128,
+            min: nanos[0],
+            median: quantile(&nanos, 0.5),
+            mean,
+            p95: quantile(&nanos, 0.95),
+            max: nanos[nanos.len() - 1],
+            std_dev,
+            outliers: nanos.iter().filter(|x| **x < low || **x > high).count() as u128,
+        })
+    }
+}
+
+/// Linear interpolation between closest ranks of sorted values.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let rank = q * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn nanos_to_string(nanos: f64) -> String {
+    format!("{:.1?}", Duration::from_nanos(nanos as u64))
+}
+
+/// Formats the spread of a benchmark as a single line.
+pub fn format_stats(stats: &BenchStats) -> String {
+    let mut s = format!(
+        "min {} · p95 {} · max {} · mean {} · σ {}",
+        nanos_to_string(stats.min),
+        nanos_to_string(stats.p95),
+        nanos_to_string(stats.max),
+        nanos_to_string(stats.mean),
+        nanos_to_string(stats.std_dev),
+    );
+
+    if stats.outliers > 0 {
+        let noun = if stats.outliers == 1 {
+            "outlier"
+        } else {
+            "outliers"
+        };
+        s.push_str(&format!(" · ⚠ {} {noun}", stats.outliers));
+    }
+
+    s
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&BenchStats> for JsonValue {
+    fn from(value: &BenchStats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        #[allow(clippy::cast_precision_loss)]
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
+        map.insert("min".into(), JsonValue::Number(value.min));
+        map.insert("median".into(), JsonValue::Number(value.median));
+        map.insert("mean".into(), JsonValue::Number(value.mean));
+        map.insert("p95".into(), JsonValue::Number(value.p95));
+        map.insert("max".into(), JsonValue::Number(value.max));
+        map.insert("std_dev".into(), JsonValue::Number(value.std_dev));
+        #[allow(clippy::cast_precision_loss)]
+        map.insert("outliers".into(), JsonValue::Number(value.outliers as f64));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for BenchStats {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected stats to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected stats.{key} to be a number."))
+        };
+
+        Ok(BenchStats {
+            samples: number("samples")? as u128,
+            min: number("min")?,
+            median: number("median")?,
+            mean: number("mean")?,
+            p95: number("p95")?,
+            max: number("max")?,
+            std_dev: number("std_dev")?,
+            outliers: number("outliers")? as u128,
+        })
+    }
+}
+
+/// Reads optional stats stored under `key` of a JSON object.
+pub fn stats_from_json(
+    json: &HashMap<String, JsonValue>,
+    key: &str,
+) -> Result<Option<BenchStats>, String> {
+    match json.get(key) {
+        None | Some(JsonValue::Null) => Ok(None),
+        Some(v) => BenchStats::try_from(v).map(Some),
+    }
+}
+
+/// Converts optional stats to JSON, using `null` if absent.
+pub fn stats_to_json(stats: Option<&BenchStats>) -> JsonValue {
+    stats.map_or(JsonValue::Null, JsonValue::from)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{parse_duration, BenchConfig, BenchStats};
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_duration("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("10µs"), Some(Duration::from_micros(10)));
+        assert_eq!(parse_duration("10us"), Some(Duration::from_micros(10)));
+        assert_eq!(parse_duration("7ns"), Some(Duration::from_nanos(7)));
+        assert_eq!(parse_duration("2m"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_duration("2"), None);
+        assert_eq!(parse_duration("fast"), None);
+        assert_eq!(parse_duration("1h"), None);
+    }
+
+    #[test]
+    fn clamps_sample_counts() {
+        let config = BenchConfig::default();
+        assert_eq!(config.sample_count(&Duration::from_secs(2)), 10);
+        assert_eq!(config.sample_count(&Duration::from_millis(10)), 100);
+        assert_eq!(config.sample_count(&Duration::from_nanos(1)), 10_000);
+    }
+
+    #[test]
+    fn computes_stats() {
+        let samples: Vec<Duration> = [10, 12, 11, 13, 12, 11, 10, 12, 11, 100]
+            .iter()
+            .map(|x| Duration::from_nanos(*x))
+            .collect();
+
+        let stats = BenchStats::from_samples(&samples).unwrap();
+
+        assert_eq!(stats.samples, 10);
+        assert_eq!(stats.min, 10.0);
+        assert_eq!(stats.max, 100.0);
+        assert_eq!(stats.median, 11.5);
+        assert_eq!(stats.mean, 20.2);
+        assert_eq!(stats.outliers, 1);
+        assert!(stats.p95 > 13.0 && stats.p95 < 100.0);
+        assert!((stats.std_dev - 28.05).abs() < 0.01);
+    }
+
+    #[test]
+    fn handles_single_samples() {
+        let stats = BenchStats::from_samples(&[Duration::from_nanos(5)]).unwrap();
+        assert_eq!(stats.median, 5.0);
+        assert_eq!(stats.std_dev, 0.0);
+        assert_eq!(stats.outliers, 0);
+        assert_eq!(BenchStats::from_samples(&[]), None);
+    }
+}
@@ -0,0 +1,139 @@
+/// The puzzle schedule of each advent of code event.
+use crate::template::{AllDays, Day, Year};
+
+/// Offset of the advent of code server's timezone (EST) to UTC, in hours.
+/// Puzzles unlock at midnight in this timezone.
+pub const SERVER_UTC_OFFSET: i32 = -5;
+
+/// First year with a shortened event.
+const SHORT_EVENTS_SINCE: u16 = 2025;
+
+/// The schedule of one event: how many puzzles there are, and when they unlock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calendar {
+    pub year: Year,
+    /// Number of puzzles, released from the 1st of december on.
+    pub days: u8,
+}
+
+impl Calendar {
+    /// Returns the calendar of an event.
+    pub const fn of(year: Year) -> Self {
+        let days = if year.into_inner() >= SHORT_EVENTS_SINCE {
+            12
+        } else {
+            25
+        };
+        Self { year, days }
+    }
+
+    /// Whether the event has a puzzle on this day.
+    pub fn contains(&self, day: Day) -> bool {
+        day.into_inner() <= self.days
+    }
+
+    /// The last puzzle of an event has a single part, the second star is awarded for finishing all others.
+    pub fn has_part_two(&self, day: Day) -> bool {
+        day.into_inner() != self.days
+    }
+
+    /// Every part of a day that can be solved.
+    pub fn parts(&self, day: Day) -> &'static [u8] {
+        if self.has_part_two(day) {
+            &[1, 2]
+        } else {
+            &[1]
+        }
+    }
+
+    /// Unlock time of a day's puzzle as seconds since the unix epoch.
+    pub fn unlock_time(&self, day: Day) -> i64 {
+        let days = days_from_civil(
+            i64::from(self.year.into_inner()),
+            12,
+            i64::from(day.into_inner()),
+        );
+        days * 86_400 - i64::from(SERVER_UTC_OFFSET) * 3_600
+    }
+
+    /// The day whose puzzle unlocked in the 24 hours before `timestamp`, in seconds since the unix epoch.
+    pub fn unlocked_at(&self, timestamp: i64) -> Option<Day> {
+        self.all_days()
+            .find(|&day| (0..86_400).contains(&(timestamp - self.unlock_time(day))))
+    }
+
+    /// Iterates all days of the event.
+    pub fn all_days(&self) -> AllDays {
+        AllDays::new(self.year)
+    }
+}
+
+/// Days since the unix epoch of a date in the proleptic gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // see: http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Calendar;
+    use crate::{day, year};
+
+    #[test]
+    fn models_event_lengths() {
+        assert_eq!(Calendar::of(year!(2015)).days, 25);
+        assert_eq!(Calendar::of(year!(2024)).days, 25);
+        assert_eq!(Calendar::of(year!(2025)).days, 12);
+
+        assert!(Calendar::of(year!(2025)).contains(day!(12)));
+        assert!(!Calendar::of(year!(2025)).contains(day!(13)));
+        assert!(Calendar::of(year!(2024)).contains(day!(25)));
+    }
+
+    #[test]
+    fn last_day_has_a_single_part() {
+        let calendar = Calendar::of(year!(2024));
+        assert!(calendar.has_part_two(day!(24)));
+        assert!(!calendar.has_part_two(day!(25)));
+        assert_eq!(calendar.parts(day!(25)), &[1]);
+
+        let calendar = Calendar::of(year!(2025));
+        assert!(!calendar.has_part_two(day!(12)));
+    }
+
+    #[test]
+    fn computes_unlock_times() {
+        // 2024-12-01T05:00:00Z
+        assert_eq!(
+            Calendar::of(year!(2024)).unlock_time(day!(1)),
+            1_733_029_200
+        );
+
+        let calendar = Calendar::of(year!(2024));
+        assert_eq!(calendar.unlocked_at(1_733_029_199), None);
+        assert_eq!(calendar.unlocked_at(1_733_029_200), Some(day!(1)));
+        assert_eq!(calendar.unlocked_at(1_733_029_200 + 86_399), Some(day!(1)));
+        assert_eq!(calendar.unlocked_at(1_733_029_200 + 86_400), Some(day!(2)));
+
+        // 2025-12-13T05:00:00Z, the event only has 12 puzzles.
+        let calendar = Calendar::of(year!(2025));
+        assert_eq!(calendar.unlocked_at(1_765_602_000), None);
+        assert_eq!(calendar.unlocked_at(1_765_602_000 - 1), Some(day!(12)));
+    }
+
+    #[test]
+    fn iterates_days_of_an_event() {
+        let days: Vec<_> = Calendar::of(year!(2025)).all_days().collect();
+        assert_eq!(days.len(), 12);
+        assert_eq!(days.first(), Some(&day!(1)));
+        assert_eq!(days.last(), Some(&day!(12)));
+    }
+}
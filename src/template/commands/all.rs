@@ -1,5 +1,19 @@
-use crate::template::{all_days, run_multi::run_multi};
+use std::process;
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+use crate::template::run_multi::{run_multi, Timeouts};
+use crate::template::{all_days, Year};
+
+pub fn handle(year: Year, is_release: bool, jobs: usize, timeouts: &Timeouts) {
+    let run = run_multi(
+        year,
+        &all_days(year).collect(),
+        is_release,
+        None,
+        jobs,
+        timeouts,
+    );
+
+    if run.has_failures {
+        process::exit(1);
+    }
 }
@@ -1,14 +1,17 @@
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_client::AocClient, Day, Year};
 use std::process;
 
-pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
-    }
+pub fn handle(year: Year, day: Day) {
+    let client = match AocClient::from_env(year) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Failed to download: {e}");
+            process::exit(1);
+        }
+    };
 
-    if let Err(e) = aoc_cli::download(day) {
-        eprintln!("failed to call aoc-cli: {e}");
+    if let Err(e) = client.download(day) {
+        eprintln!("Failed to download: {e}");
         process::exit(1);
     };
 }
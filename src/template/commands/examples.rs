@@ -0,0 +1,381 @@
+use std::{
+    fs,
+    io::{self, BufRead, IsTerminal, Write},
+    ops::Range,
+    path::Path,
+    process,
+};
+
+use crate::template::aoc_client::get_puzzle_path;
+use crate::template::puzzle_examples::{BlockGuess, Puzzle, PuzzlePart};
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::{data_dir, Day, Year};
+
+/// Lines of a code block shown when asking which block is the example.
+const PREVIEW_LINES: usize = 6;
+
+/// Names of the tests that the module template generates for each part.
+const TEST_NAMES: [&str; 2] = ["test_part_one", "test_part_two"];
+
+/// The assertion of a template test that has no expected answer yet.
+const UNSET_ASSERTION: &str = "assert_eq!(result, None);";
+
+pub fn handle(year: Year, day: Day) {
+    let puzzle_path = get_puzzle_path(year, day);
+    let markdown = match fs::read_to_string(&puzzle_path) {
+        Ok(markdown) => markdown,
+        Err(e) => {
+            eprintln!("Failed to read puzzle \"{}\": {e}", puzzle_path.display());
+            eprintln!("Type `cargo download {day}` to download the puzzle first.");
+            process::exit(1);
+        }
+    };
+
+    let puzzle = Puzzle::parse(&markdown);
+    let examples_dir = data_dir(year).join("examples");
+
+    let example = puzzle
+        .part(1)
+        .and_then(|part| choose_block(part, 1, puzzle.guess_example()));
+    let part_two_example = puzzle.part(2).and_then(|part| {
+        choose_block(
+            part,
+            2,
+            puzzle.guess_part_two_example(example.map(String::as_str)),
+        )
+    });
+
+    match example {
+        Some(content) => write_example(&examples_dir.join(format!("{day}.txt")), content),
+        None => println!("Found no example in the puzzle."),
+    }
+    if let Some(content) = part_two_example {
+        write_example(&examples_dir.join(format!("{day}-2.txt")), content);
+    }
+
+    let answers = [puzzle.answer(1), puzzle.answer(2)];
+    for (part, answer) in (1..).zip(answers) {
+        if let Some(answer) = answer {
+            println!("Expected answer of part {part}: {answer}");
+        }
+    }
+
+    let module_path = get_path_for_bin(year, day);
+    let Ok(module) = fs::read_to_string(&module_path) else {
+        println!("---");
+        println!("🎄 Type `cargo scaffold {day}` to create the module, then run `cargo examples {day}` again to fill in its tests.");
+        return;
+    };
+
+    let (updated, filled) = fill_in_tests(&module, answers, part_two_example.is_some());
+    for (name, filled) in TEST_NAMES.iter().zip(filled) {
+        if !filled {
+            println!("Left `{name}` unchanged, it already expects an answer or none was found.");
+        }
+    }
+
+    if updated != module {
+        if let Err(e) = fs::write(&module_path, updated) {
+            eprintln!("Failed to write module file: {e}");
+            process::exit(1);
+        }
+        println!("Filled in the expected answers in \"{module_path}\"");
+    }
+}
+
+/// Returns the content of the block holding the example, asking the user if the guess is ambiguous.
+fn choose_block(part: &PuzzlePart, number: u8, guess: BlockGuess) -> Option<&String> {
+    let (candidates, likely) = match guess {
+        BlockGuess::None => return None,
+        BlockGuess::Certain(i) => return Some(&part.blocks[i].content),
+        BlockGuess::Ambiguous { candidates, likely } => (candidates, likely),
+    };
+
+    println!("Could not tell which code block is the example of part {number}:");
+    for (n, &i) in (1..).zip(&candidates) {
+        println!();
+        println!("[{n}] after \"{}\"", part.blocks[i].context);
+        println!("{}", preview(&part.blocks[i].content));
+    }
+    println!();
+
+    let content = |n: usize| &part.blocks[candidates[n]].content;
+    let default = likely.and_then(|l| candidates.iter().position(|&c| c == l));
+    let question = format!(
+        "Which one is the example of part {number}? [1-{}, 0 for none, Enter for {}] ",
+        candidates.len(),
+        default.map_or("none".to_string(), |d| (d + 1).to_string())
+    );
+
+    loop {
+        let Some(answer) = ask(&question) else {
+            println!(
+                "Not running in a terminal, picked {}.",
+                default.map_or("none".to_string(), |d| format!("[{}]", d + 1))
+            );
+            return default.map(content);
+        };
+
+        match answer.parse::<usize>() {
+            _ if answer.is_empty() => return default.map(content),
+            Ok(0) => return None,
+            Ok(n) if n <= candidates.len() => return Some(content(n - 1)),
+            _ => println!("Please enter a number between 0 and {}.", candidates.len()),
+        }
+    }
+}
+
+/// Writes an example file, asking before replacing a different example.
+fn write_example(path: &Path, content: &str) {
+    match fs::read_to_string(path) {
+        Ok(existing) if existing == content => {
+            println!("Example file \"{}\" is up to date", path.display());
+            return;
+        }
+        Ok(existing) if !existing.trim().is_empty() => {
+            let question = format!(
+                "\"{}\" already holds a different example. Replace it? [y/N] ",
+                path.display()
+            );
+            if !ask(&question).is_some_and(|answer| answer.eq_ignore_ascii_case("y")) {
+                println!("Kept example file \"{}\"", path.display());
+                return;
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(parent) = path.parent() {
+        if let Err(e) = fs::create_dir_all(parent) {
+            eprintln!("Failed to create data directory: {e}");
+            process::exit(1);
+        }
+    }
+
+    match fs::write(path, content) {
+        Ok(()) => println!("Wrote example file \"{}\"", path.display()),
+        Err(e) => {
+            eprintln!("Failed to write example file: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Asks a question on the terminal and returns the trimmed answer.
+/// Returns [`None`] if stdin is not a terminal or was closed.
+fn ask(question: &str) -> Option<String> {
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        return None;
+    }
+
+    print!("{question}");
+    io::stdout().flush().ok()?;
+
+    let mut answer = String::new();
+    match stdin.lock().read_line(&mut answer) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(answer.trim().to_string()),
+    }
+}
+
+fn preview(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut preview = lines
+        .iter()
+        .take(PREVIEW_LINES)
+        .map(|line| format!("    {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if lines.len() > PREVIEW_LINES {
+        preview.push_str(&format!(
+            "\n    ... {} more lines",
+            lines.len() - PREVIEW_LINES
+        ));
+    }
+
+    preview
+}
+
+/// Fills the expected answers into the template tests of a module that still expect `None`.
+/// When part two has its own example, its test reads `NN-2.txt` instead.
+/// Returns the updated module and whether each test was filled in.
+fn fill_in_tests(
+    module: &str,
+    answers: [Option<&str>; 2],
+    part_two_example: bool,
+) -> (String, [bool; 2]) {
+    let mut module = module.to_string();
+    let mut filled = [false; 2];
+
+    for (i, (part, answer)) in (1..).zip(answers).enumerate() {
+        let Some(answer) = answer else {
+            continue;
+        };
+        let example = part == 2 && part_two_example;
+        let updated = fill_in_macro(&module, part, example, answer)
+            .or_else(|| fill_in_test_fn(&module, part, example, answer));
+
+        if let Some(updated) = updated {
+            module = updated;
+            filled[i] = true;
+        }
+    }
+
+    (module, filled)
+}
+
+/// Whether the test of a part still expects no answer, as generated by the module template.
+pub(crate) fn is_test_unset(module: &str, part: u8) -> bool {
+    module.contains(&unset_entry(part))
+        || test_fn_range(module, part).is_some_and(|range| module[range].contains(UNSET_ASSERTION))
+}
+
+/// The entry of `example_tests!` that the module template generates for a part.
+fn unset_entry(part: u8) -> String {
+    format!("{part} => [(None, None)]")
+}
+
+/// Fills in an entry of `example_tests!` such as `1 => [(None, None)]`.
+fn fill_in_macro(module: &str, part: u8, example: bool, answer: &str) -> Option<String> {
+    let unset = unset_entry(part);
+    if !module.contains(&unset) {
+        return None;
+    }
+
+    let example = if example { "Some(2)" } else { "None" };
+    let entry = format!("{part} => [({example}, {})]", answer_literal(answer));
+    Some(module.replacen(&unset, &entry, 1))
+}
+
+/// Fills in a hand-written test function, as generated by earlier versions of the template.
+fn fill_in_test_fn(module: &str, part: u8, example: bool, answer: &str) -> Option<String> {
+    let range = test_fn_range(module, part)?;
+    let (start, end) = (range.start, range.end);
+
+    let body = &module[range];
+    if !body.contains(UNSET_ASSERTION) {
+        return None;
+    }
+
+    let mut body = body.replacen(
+        UNSET_ASSERTION,
+        &format!("assert_eq!(result, Some({}));", answer_literal(answer)),
+        1,
+    );
+    if example {
+        body = body.replacen(
+            "read_file(\"examples\", YEAR, DAY)",
+            "read_file_part(\"examples\", YEAR, DAY, 2)",
+            1,
+        );
+    }
+
+    let mut module = module.to_string();
+    module.replace_range(start..end, &body);
+    Some(module)
+}
+
+/// Where the hand-written test function of a part is in a module.
+fn test_fn_range(module: &str, part: u8) -> Option<Range<usize>> {
+    let name = TEST_NAMES.get(usize::from(part).checked_sub(1)?)?;
+    let start = module.find(&format!("fn {name}()"))?;
+    // a test ends where the next one starts.
+    let end = module[start..]
+        .find("#[test]")
+        .map_or(module.len(), |offset| start + offset);
+
+    Some(start..end)
+}
+
+/// Writes an answer as a rust literal, as a string unless it is an integer.
+fn answer_literal(answer: &str) -> String {
+    if answer.parse::<i64>().is_ok() {
+        answer.to_string()
+    } else {
+        format!("{answer:?}.to_string()")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{answer_literal, fill_in_tests, is_test_unset, preview};
+
+    const MODULE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
+
+    /// Tests as generated before the template used `example_tests!`.
+    const HANDWRITTEN: &str = r#"
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
+        assert_eq!(result, None);
+    }
+"#;
+
+    #[test]
+    fn fills_in_template_tests() {
+        let (module, filled) = fill_in_tests(MODULE, [Some("11"), Some("31")], false);
+
+        assert_eq!(filled, [true, true]);
+        assert!(module.contains("1 => [(None, 11)],"));
+        assert!(module.contains("2 => [(None, 31)],"));
+
+        // tests that expect an answer are left alone.
+        let (again, filled) = fill_in_tests(&module, [Some("12"), Some("32")], false);
+        assert_eq!(filled, [false, false]);
+        assert_eq!(again, module);
+    }
+
+    #[test]
+    fn reads_second_example_in_part_two() {
+        let (module, filled) = fill_in_tests(MODULE, [None, Some("ABC")], true);
+
+        assert_eq!(filled, [false, true]);
+        assert!(module.contains("1 => [(None, None)],"));
+        assert!(module.contains("2 => [(Some(2), \"ABC\".to_string())],"));
+    }
+
+    #[test]
+    fn fills_in_handwritten_tests() {
+        let (module, filled) = fill_in_tests(HANDWRITTEN, [None, Some("31")], true);
+
+        assert_eq!(filled, [false, true]);
+        let part_two = &module[module.find("fn test_part_two").unwrap()..];
+        assert!(part_two.contains("read_file_part(\"examples\", YEAR, DAY, 2)"));
+        assert!(part_two.contains("assert_eq!(result, Some(31));"));
+        assert_eq!(module.matches("assert_eq!(result, None);").count(), 1);
+    }
+
+    #[test]
+    fn finds_unset_tests() {
+        assert!(is_test_unset(MODULE, 1));
+        assert!(is_test_unset(MODULE, 2));
+
+        let (module, _) = fill_in_tests(MODULE, [Some("11"), None], false);
+        assert!(!is_test_unset(&module, 1));
+        assert!(is_test_unset(&module, 2));
+
+        let (module, _) = fill_in_tests(HANDWRITTEN, [None, Some("31")], false);
+        assert!(is_test_unset(&module, 1));
+        assert!(!is_test_unset(&module, 2));
+    }
+
+    #[test]
+    fn formats_answers() {
+        assert_eq!(answer_literal("-42"), "-42");
+        assert_eq!(answer_literal("6,1,4"), "\"6,1,4\".to_string()");
+        assert_eq!(
+            preview("1\n2\n3\n4\n5\n6\n7\n8\n"),
+            "    1\n    2\n    3\n    4\n    5\n    6\n    ... 2 more lines"
+        );
+    }
+}
@@ -1,15 +1,21 @@
 use std::process;
 
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_client::AocClient, Day, Year};
 
-pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
-    }
+pub fn handle(year: Year, day: Day) {
+    let client = match AocClient::from_env(year) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Failed to read puzzle: {e}");
+            process::exit(1);
+        }
+    };
 
-    if let Err(e) = aoc_cli::read(day) {
-        eprintln!("failed to call aoc-cli: {e}");
-        process::exit(1);
+    match client.read(day) {
+        Ok(puzzle) => println!("{puzzle}"),
+        Err(e) => {
+            eprintln!("Failed to read puzzle: {e}");
+            process::exit(1);
+        }
     };
 }
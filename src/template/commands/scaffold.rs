@@ -1,79 +1,512 @@
 use std::{
-    fs::{File, OpenOptions},
-    io::Write,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
     process,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use crate::template::Day;
+use crate::template::aoc_client::get_puzzle_path;
+use crate::template::history::format_timestamp;
+use crate::template::run_multi::get_bin_name;
+use crate::template::{data_dir, Day, Year};
 
 const MODULE_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
 
-fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
-    let mut file = OpenOptions::new();
-    if overwrite {
-        file.create(true);
-    } else {
-        file.create_new(true);
+/// Directory holding the named module templates, e.g. `templates/grid.txt`.
+const TEMPLATES_DIR: &str = "templates";
+
+/// Template used when no `--template` is passed. `templates/default.txt` replaces the built-in one.
+pub const DEFAULT_TEMPLATE: &str = "default";
+
+/// Answer type of both parts when no `--answer-type` is passed.
+pub const DEFAULT_ANSWER_TYPE: &str = "u32";
+
+pub struct Options {
+    pub template: String,
+    pub answer_type: String,
+    /// Replace existing files, after backing them up.
+    pub overwrite: bool,
+    /// Only print what would be created.
+    pub dry_run: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            template: DEFAULT_TEMPLATE.into(),
+            answer_type: DEFAULT_ANSWER_TYPE.into(),
+            overwrite: false,
+            dry_run: false,
+        }
     }
-    file.truncate(true).write(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)
+/// A file created by scaffolding a day.
+struct ScaffoldFile {
+    /// What the file holds, e.g. `input`.
+    kind: &'static str,
+    path: PathBuf,
+    contents: String,
 }
 
-pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+/// What scaffolding does with a file.
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    Create,
+    /// The file exists and is left alone.
+    Skip,
+    /// The file exists and is replaced. A non-empty file is backed up to the path first.
+    Overwrite {
+        backup: Option<PathBuf>,
+    },
+}
 
-    let mut file = match safe_create_file(&module_path, overwrite) {
-        Ok(file) => file,
+impl Action {
+    fn plan(path: &Path, overwrite: bool, timestamp: u64) -> Self {
+        match fs::metadata(path) {
+            Err(_) => Action::Create,
+            Ok(_) if !overwrite => Action::Skip,
+            Ok(metadata) => Action::Overwrite {
+                backup: (metadata.len() > 0).then(|| backup_path(path, timestamp)),
+            },
+        }
+    }
+}
+
+pub fn handle(year: Year, day: Day, options: &Options) {
+    let data_dir = data_dir(year);
+
+    let template = match load_template(Path::new(TEMPLATES_DIR), &options.template) {
+        Ok(template) => template,
         Err(e) => {
-            eprintln!("Failed to create module file: {e}");
+            eprintln!("{e}");
+            eprintln!("Type `cargo scaffold templates` to list the available templates.");
             process::exit(1);
         }
     };
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
+    let variables = Variables {
+        year,
+        day,
+        title: puzzle_title(year, day),
+        answer_type: options.answer_type.clone(),
+    };
+    let module = variables.render(&template);
+
+    let unknown = unknown_variables(&module);
+    if !unknown.is_empty() {
+        eprintln!(
+            "Template \"{}\" uses unknown variables: {}",
+            options.template,
+            unknown.join(", ")
+        );
+        process::exit(1);
+    }
+
+    let files = [
+        ScaffoldFile {
+            kind: "module",
+            path: PathBuf::from(format!("src/bin/{}.rs", get_bin_name(year, day))),
+            contents: module,
+        },
+        ScaffoldFile {
+            kind: "input",
+            path: data_dir.join("inputs").join(format!("{day}.txt")),
+            contents: String::new(),
+        },
+        ScaffoldFile {
+            kind: "example",
+            path: data_dir.join("examples").join(format!("{day}.txt")),
+            contents: String::new(),
+        },
+    ];
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+
+    let mut skipped = false;
+    for file in &files {
+        let action = Action::plan(&file.path, options.overwrite, timestamp);
+        skipped |= action == Action::Skip;
+
+        if options.dry_run {
+            println!("{}", describe(file, &action, true));
+        } else if let Err(e) = apply(file, &action) {
+            eprintln!("Failed to create {} file: {e}", file.kind);
             process::exit(1);
+        } else {
+            println!("{}", describe(file, &action, false));
         }
     }
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create input file: {e}");
-            process::exit(1);
+    println!("---");
+    if options.dry_run {
+        println!(
+            "🎄 Nothing was written, run the command without `--dry-run` to scaffold the day."
+        );
+        return;
+    }
+    if skipped && !options.overwrite {
+        println!("Existing files were kept, pass `--overwrite` to replace them with backups.");
+    }
+    // only mention the year when it differs from the configured default.
+    if Year::from_env() == Some(year) {
+        println!("🎄 Type `cargo solve {day}` to run your solution.");
+    } else {
+        println!("🎄 Type `cargo solve {day} --year {year}` to run your solution.");
+    }
+}
+
+fn apply(file: &ScaffoldFile, action: &Action) -> io::Result<()> {
+    match action {
+        Action::Skip => return Ok(()),
+        Action::Overwrite {
+            backup: Some(backup),
+        } => {
+            fs::copy(&file.path, backup)?;
         }
+        Action::Create | Action::Overwrite { backup: None } => {}
+    }
+
+    if let Some(parent) = file.path.parent() {
+        fs::create_dir_all(parent)?;
     }
+    fs::write(&file.path, &file.contents)
+}
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
+/// Describes an action, or what it would do in a dry run.
+fn describe(file: &ScaffoldFile, action: &Action, dry_run: bool) -> String {
+    let empty = if file.contents.is_empty() {
+        "empty "
+    } else {
+        ""
+    };
+    let path = file.path.display();
+    let kind = file.kind;
+
+    match (action, dry_run) {
+        (Action::Create, false) => format!("Created {empty}{kind} file \"{path}\""),
+        (Action::Create, true) => format!("Would create {empty}{kind} file \"{path}\""),
+        (Action::Skip, false) => format!("Skipped existing {kind} file \"{path}\""),
+        (Action::Skip, true) => format!("Would skip existing {kind} file \"{path}\""),
+        (Action::Overwrite { backup }, _) => {
+            let backup = backup.as_ref().map_or(String::new(), |backup| {
+                format!(" after backing it up to \"{}\"", backup.display())
+            });
+            if dry_run {
+                format!("Would overwrite {kind} file \"{path}\"{backup}")
+            } else {
+                format!("Overwrote {kind} file \"{path}\"{backup}")
+            }
         }
+    }
+}
+
+/// A path next to `path` for its backup, e.g. `01.txt.2024-12-01-05-12.bak`.
+/// A number is added if a backup was already made in the same minute.
+fn backup_path(path: &Path, timestamp: u64) -> PathBuf {
+    let stamp = format_timestamp(timestamp).replace([' ', ':'], "-");
+    let name = path
+        .file_name()
+        .map_or(String::new(), |name| name.to_string_lossy().into_owned());
+
+    (1..)
+        .map(|n| {
+            let suffix = if n == 1 {
+                String::new()
+            } else {
+                format!("-{n}")
+            };
+            path.with_file_name(format!("{name}.{stamp}{suffix}.bak"))
+        })
+        .find(|backup| !backup.exists())
+        .unwrap()
+}
+
+/// Prints the templates that `--template` accepts.
+pub fn list_templates() {
+    let templates = match find_templates(Path::new(TEMPLATES_DIR)) {
+        Ok(templates) => templates,
         Err(e) => {
-            eprintln!("Failed to create example file: {e}");
+            eprintln!("Failed to read templates directory \"{TEMPLATES_DIR}\": {e}");
             process::exit(1);
         }
+    };
+
+    if !templates.contains(&DEFAULT_TEMPLATE.to_string()) {
+        println!("{DEFAULT_TEMPLATE} (built-in)");
+    }
+    for name in templates {
+        let path = template_path(Path::new(TEMPLATES_DIR), &name);
+        println!("{name} ({})", path.display());
     }
 
     println!("---");
-    println!("🎄 Type `cargo solve {day}` to run your solution.");
+    println!("🎄 Type `cargo scaffold <day> --template <name>` to scaffold a day from a template.");
+}
+
+/// Names of the templates in `dir`, sorted. A missing directory holds no templates.
+fn find_templates(dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+
+    let mut names = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "txt") {
+            if let Some(name) = path.file_stem() {
+                names.push(name.to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    names.sort();
+    Ok(names)
+}
+
+fn template_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{name}.txt"))
+}
+
+/// Reads the template called `name` from `dir`, falling back to the built-in template for the default.
+fn load_template(dir: &Path, name: &str) -> Result<String, TemplateError> {
+    // names are file stems, so they can't point outside of the templates directory.
+    if name.is_empty() || name.contains(['/', '\\', '.']) {
+        return Err(TemplateError::InvalidName(name.into()));
+    }
+
+    let path = template_path(dir, name);
+    match fs::read_to_string(&path) {
+        Ok(template) => Ok(template),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            if name == DEFAULT_TEMPLATE {
+                Ok(MODULE_TEMPLATE.into())
+            } else {
+                Err(TemplateError::NotFound(path))
+            }
+        }
+        Err(e) => Err(TemplateError::Io(path, e)),
+    }
+}
+
+/// The title of a downloaded puzzle, e.g. `Day 1: Historian Hysteria`, or `Day 1` otherwise.
+fn puzzle_title(year: Year, day: Day) -> String {
+    fs::read_to_string(get_puzzle_path(year, day))
+        .ok()
+        .and_then(|markdown| parse_title(&markdown))
+        .unwrap_or_else(|| format!("Day {}", day.into_inner()))
+}
+
+/// Finds the title in the heading of a puzzle description, e.g. `## --- Day 1: Historian Hysteria ---`.
+fn parse_title(markdown: &str) -> Option<String> {
+    let heading = markdown.lines().find(|line| line.starts_with("## --- "))?;
+    let title = heading
+        .trim_start_matches("## --- ")
+        .trim_end_matches(" ---")
+        .trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Values substituted for the `%NAME%` variables of a template.
+struct Variables {
+    year: Year,
+    day: Day,
+    title: String,
+    answer_type: String,
+}
+
+impl Variables {
+    fn render(&self, template: &str) -> String {
+        [
+            ("%YEAR%", self.year.to_string()),
+            ("%DAY%", self.day.to_string()),
+            ("%DAY_NUMBER%", self.day.into_inner().to_string()),
+            ("%TITLE%", self.title.clone()),
+            ("%ANSWER_TYPE%", self.answer_type.clone()),
+        ]
+        .iter()
+        .fold(template.to_string(), |module, (variable, value)| {
+            module.replace(variable, value)
+        })
+    }
+}
+
+/// Variables left in a rendered template, which are usually typos.
+fn unknown_variables(module: &str) -> Vec<String> {
+    let pattern = regex::Regex::new("%[A-Z][A-Z_]*%").unwrap();
+    let mut unknown: Vec<String> = vec![];
+    for variable in pattern.find_iter(module) {
+        if !unknown.iter().any(|v| v == variable.as_str()) {
+            unknown.push(variable.as_str().to_string());
+        }
+    }
+    unknown
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug)]
+enum TemplateError {
+    InvalidName(String),
+    NotFound(PathBuf),
+    Io(PathBuf, io::Error),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::InvalidName(name) => {
+                write!(f, "\"{name}\" is not a template name")
+            }
+            TemplateError::NotFound(path) => {
+                write!(f, "Template \"{}\" does not exist", path.display())
+            }
+            TemplateError::Io(path, e) => {
+                write!(f, "Failed to read template \"{}\": {e}", path.display())
+            }
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use super::{
+        apply, backup_path, find_templates, load_template, parse_title, unknown_variables, Action,
+        ScaffoldFile, TemplateError, Variables, MODULE_TEMPLATE,
+    };
+    use crate::{day, year};
+
+    fn variables() -> Variables {
+        Variables {
+            year: year!(2024),
+            day: day!(5),
+            title: "Day 5: Print Queue".into(),
+            answer_type: "u64".into(),
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-scaffold-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn renders_variables() {
+        let module = variables().render(
+            "// %TITLE% (%YEAR%)\nsolution!(%DAY_NUMBER%);\nstruct Day%DAY%;\nfn a() -> Option<%ANSWER_TYPE%> {}",
+        );
+        assert_eq!(
+            module,
+            "// Day 5: Print Queue (2024)\nsolution!(5);\nstruct Day05;\nfn a() -> Option<u64> {}"
+        );
+        assert!(unknown_variables(&variables().render(MODULE_TEMPLATE)).is_empty());
+    }
+
+    #[test]
+    fn finds_unknown_variables() {
+        assert_eq!(
+            unknown_variables("%DAYS% %TITEL% a % b %DAYS%"),
+            vec!["%DAYS%", "%TITEL%"]
+        );
+    }
+
+    #[test]
+    fn loads_named_templates() {
+        let dir = temp_dir("load");
+        fs::write(dir.join("grid.txt"), "grid").unwrap();
+        fs::write(dir.join("notes.md"), "not a template").unwrap();
+
+        assert_eq!(load_template(&dir, "grid").unwrap(), "grid");
+        assert_eq!(load_template(&dir, "default").unwrap(), MODULE_TEMPLATE);
+        assert!(matches!(
+            load_template(&dir, "sim"),
+            Err(TemplateError::NotFound(_))
+        ));
+        assert!(matches!(
+            load_template(&dir, "../grid"),
+            Err(TemplateError::InvalidName(_))
+        ));
+        assert_eq!(find_templates(&dir).unwrap(), vec!["grid"]);
+
+        // a default template in the directory replaces the built-in one.
+        fs::write(dir.join("default.txt"), "custom").unwrap();
+        assert_eq!(load_template(&dir, "default").unwrap(), "custom");
+        assert_eq!(find_templates(&dir).unwrap(), vec!["default", "grid"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(find_templates(&dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parses_puzzle_titles() {
+        assert_eq!(
+            parse_title("## --- Day 1: Historian Hysteria ---\n\nText.").as_deref(),
+            Some("Day 1: Historian Hysteria")
+        );
+        assert_eq!(parse_title("No heading."), None);
+    }
+
+    #[test]
+    fn keeps_existing_files() {
+        let dir = temp_dir("keep");
+        let input = dir.join("01.txt");
+        let timestamp = 1_733_029_200;
+
+        assert_eq!(Action::plan(&input, false, timestamp), Action::Create);
+
+        fs::write(&input, "my input").unwrap();
+        assert_eq!(Action::plan(&input, false, timestamp), Action::Skip);
+
+        let backup = dir.join("01.txt.2024-12-01-05-00.bak");
+        let action = Action::plan(&input, true, timestamp);
+        assert_eq!(
+            action,
+            Action::Overwrite {
+                backup: Some(backup.clone())
+            }
+        );
+
+        let file = ScaffoldFile {
+            kind: "input",
+            path: input.clone(),
+            contents: String::new(),
+        };
+        apply(&file, &action).unwrap();
+        assert_eq!(fs::read_to_string(&input).unwrap(), "");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "my input");
+
+        // empty files have nothing worth backing up.
+        assert_eq!(
+            Action::plan(&input, true, timestamp),
+            Action::Overwrite { backup: None }
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn numbers_backups_of_the_same_minute() {
+        let dir = temp_dir("backup");
+        let input = dir.join("01.txt");
+
+        fs::write(dir.join("01.txt.2024-12-01-05-00.bak"), "first").unwrap();
+        assert_eq!(
+            backup_path(&input, 1_733_029_210),
+            dir.join("01.txt.2024-12-01-05-00-2.bak")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
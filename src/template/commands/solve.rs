@@ -1,9 +1,34 @@
-use std::process::{Command, Stdio};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime};
 
-use crate::template::Day;
+use crate::template::input::InputSource;
+use crate::template::protocol::SolutionReport;
+use crate::template::run_multi::child_commands::{find_answer, run_solution};
+use crate::template::run_multi::{get_bin_name, get_path_for_bin, Timeouts};
+use crate::template::{data_dir, Day, Year, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+/// How often watched files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Clears the terminal and moves the cursor to the top left corner.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+pub fn handle(
+    year: Year,
+    day: Day,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    input: &InputSource,
+) {
+    let mut cmd_args = vec![
+        "run".to_string(),
+        "--bin".to_string(),
+        get_bin_name(year, day),
+    ];
 
     if dhat {
         cmd_args.extend([
@@ -23,6 +48,8 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
+    cmd_args.extend(input.to_args());
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
@@ -30,5 +57,252 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         .spawn()
         .unwrap();
 
-    cmd.wait().unwrap();
+    // a failed or crashed solution fails the command too.
+    let status = cmd.wait().unwrap();
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
+}
+
+/// Runs the tests of a day, exiting with their status if they fail.
+pub fn test(year: Year, day: Day, release: bool) {
+    let status = run_tests(year, day, release);
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
+}
+
+fn run_tests(year: Year, day: Day, release: bool) -> ExitStatus {
+    let mut cmd_args = vec![
+        "test".to_string(),
+        "--bin".to_string(),
+        get_bin_name(year, day),
+    ];
+
+    if release {
+        cmd_args.push("--release".to_string());
+    }
+
+    let mut cmd = Command::new("cargo")
+        .args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+
+    cmd.wait().unwrap()
+}
+
+/// Re-runs a day on `input`, or its tests, whenever its module, input or examples change.
+/// Runs until interrupted.
+pub fn watch(year: Year, day: Day, release: bool, test_only: bool, input: &InputSource) {
+    let mut previous: Option<SolutionReport> = None;
+
+    loop {
+        let snapshot = modified_times(&watched_files(year, day, input));
+
+        print!("{CLEAR_SCREEN}");
+        println!(
+            "{ANSI_ITALIC}Watching day {day} ({year}) for changes, press Ctrl-C to stop.{ANSI_RESET}"
+        );
+        println!();
+
+        if test_only {
+            // a failed test run should not stop watching.
+            run_tests(year, day, release);
+        } else {
+            let mut stdout = io::stdout();
+            let reports = run_solution(
+                year,
+                day,
+                None,
+                release,
+                &Timeouts::default(),
+                input,
+                true,
+                &mut stdout,
+            )
+            .unwrap_or_default();
+
+            if let Some(previous) = &previous {
+                println!();
+                println!("{ANSI_BOLD}Since last run{ANSI_RESET}");
+                for line in answer_changes(previous, &reports) {
+                    println!("{line}");
+                }
+            }
+
+            previous = Some(reports);
+        }
+
+        let _ = io::stdout().flush();
+
+        // files can appear or disappear, e.g. when adding a second example.
+        while modified_times(&watched_files(year, day, input)) == snapshot {
+            thread::sleep(POLL_INTERVAL);
+        }
+
+        // give editors a moment to finish writing before rebuilding.
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// The module, input and example files of a day, and the file of `input` if it is another one.
+fn watched_files(year: Year, day: Day, input: &InputSource) -> Vec<PathBuf> {
+    let data_dir = data_dir(year);
+    let mut files = vec![
+        PathBuf::from(get_path_for_bin(year, day)),
+        data_dir.join("inputs").join(format!("{day}.txt")),
+    ];
+    if let Some(path) = input.path(year, day) {
+        if !files.contains(&path) {
+            files.push(path);
+        }
+    }
+
+    // examples are named `01.txt` or `01-2.txt`.
+    let examples_dir = data_dir.join("examples");
+    if let Ok(entries) = examples_dir.read_dir() {
+        let mut examples: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| is_example_of(path, day))
+            .collect();
+        examples.sort_unstable();
+        files.extend(examples);
+    }
+
+    files
+}
+
+fn is_example_of(path: &Path, day: Day) -> bool {
+    let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
+        return false;
+    };
+    let Some(stem) = name.strip_suffix(".txt") else {
+        return false;
+    };
+    let day = day.to_string();
+    stem == day || stem.strip_prefix(&day).is_some_and(|s| s.starts_with('-'))
+}
+
+fn modified_times(files: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    files
+        .iter()
+        .map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok();
+            (path.clone(), modified)
+        })
+        .collect()
+}
+
+/// Describes how the answer of every part changed between two runs.
+fn answer_changes(previous: &SolutionReport, current: &SolutionReport) -> Vec<String> {
+    let format_answer = |answer: &Option<String>| answer.clone().unwrap_or_else(|| "✖".into());
+
+    [1, 2]
+        .into_iter()
+        .filter_map(|part| {
+            let old = find_answer(&previous.parts, part);
+            let new = find_answer(&current.parts, part);
+
+            if old.is_none() && new.is_none() {
+                return None;
+            }
+
+            let change = if old == new {
+                format!("unchanged ({})", format_answer(&new))
+            } else {
+                format!("{} → {}", format_answer(&old), format_answer(&new))
+            };
+
+            Some(format!("Part {part}: {change}"))
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{answer_changes, is_example_of, watched_files};
+    use crate::template::input::InputSource;
+    use crate::template::protocol::{PartReport, PartStatus, SolutionReport};
+    use crate::{day, year};
+
+    fn reports(answers: [Option<&str>; 2]) -> SolutionReport {
+        let parts = answers
+            .iter()
+            .zip(1..)
+            .map(|(answer, part)| PartReport {
+                part,
+                answer: answer.map(Into::into),
+                nanos: 1.0,
+                samples: 1,
+                status: if answer.is_some() {
+                    PartStatus::Solved
+                } else {
+                    PartStatus::Unsolved
+                },
+                stats: None,
+                memory: None,
+                error: vec![],
+            })
+            .collect();
+        SolutionReport {
+            parse: None,
+            parts,
+            crashed: None,
+        }
+    }
+
+    #[test]
+    fn describes_answer_changes() {
+        let previous = reports([Some("41"), None]);
+        let current = reports([Some("42"), Some("7")]);
+
+        assert_eq!(
+            answer_changes(&previous, &current),
+            vec!["Part 1: 41 → 42", "Part 2: ✖ → 7"]
+        );
+        assert_eq!(
+            answer_changes(&current, &current),
+            vec!["Part 1: unchanged (42)", "Part 2: unchanged (7)"]
+        );
+        assert!(answer_changes(&reports([None, None]), &reports([None, None])).is_empty());
+    }
+
+    #[test]
+    fn matches_example_files() {
+        assert!(is_example_of(
+            Path::new("data/2024/examples/01.txt"),
+            day!(1)
+        ));
+        assert!(is_example_of(
+            Path::new("data/2024/examples/01-2.txt"),
+            day!(1)
+        ));
+        assert!(!is_example_of(
+            Path::new("data/2024/examples/11.txt"),
+            day!(1)
+        ));
+        assert!(!is_example_of(
+            Path::new("data/2024/examples/012.txt"),
+            day!(1)
+        ));
+    }
+
+    #[test]
+    fn watches_the_chosen_input() {
+        let input = InputSource::File(PathBuf::from("other/input.txt"));
+        let files = watched_files(year!(2024), day!(1), &input);
+        assert!(files.contains(&PathBuf::from("other/input.txt")));
+
+        // the puzzle input is always watched, and only once.
+        let files = watched_files(year!(2024), day!(1), &InputSource::Puzzle);
+        let inputs = files.iter().filter(|f| f.ends_with("inputs/01.txt"));
+        assert_eq!(inputs.count(), 1);
+    }
 }
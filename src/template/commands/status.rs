@@ -0,0 +1,276 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use tinyjson::JsonValue;
+
+use crate::template::answers::Answers;
+use crate::template::aoc_client::get_puzzle_path;
+use crate::template::commands::examples::is_test_unset;
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::timings::Timings;
+use crate::template::{all_days, data_dir, Calendar, Day, Year, ANSI_BOLD, ANSI_RESET};
+
+/// What is known about a day, from the files it left behind.
+#[derive(Debug)]
+struct DayStatus {
+    day: Day,
+    /// The parts the event has on this day.
+    parts: &'static [u8],
+    has_bin: bool,
+    has_input: bool,
+    has_example: bool,
+    has_puzzle: bool,
+    /// Parts whose test still expects no answer. Empty if there is no module.
+    unset_tests: Vec<u8>,
+    /// Stored timings of both parts.
+    timings: [Option<String>; 2],
+    /// Accepted answers of both parts, each of which earned a star.
+    answers: [Option<String>; 2],
+}
+
+impl DayStatus {
+    fn collect(year: Year, day: Day, answers: &Answers, timings: &Timings) -> Self {
+        let data_dir = data_dir(year);
+        let parts = Calendar::of(year).parts(day);
+        let module = fs::read_to_string(get_path_for_bin(year, day)).ok();
+        let timing = timings.data.iter().find(|t| t.day == day);
+        let answer = answers.get(day);
+
+        Self {
+            day,
+            parts,
+            has_bin: module.is_some(),
+            has_input: is_non_empty(&data_dir.join("inputs").join(format!("{day}.txt"))),
+            has_example: is_non_empty(&data_dir.join("examples").join(format!("{day}.txt"))),
+            has_puzzle: is_non_empty(&get_puzzle_path(year, day)),
+            unset_tests: module.map_or(vec![], |module| {
+                parts
+                    .iter()
+                    .copied()
+                    .filter(|&part| is_test_unset(&module, part))
+                    .collect()
+            }),
+            timings: [
+                timing.and_then(|t| t.part_1.clone()),
+                timing.and_then(|t| t.part_2.clone()),
+            ],
+            answers: [1, 2].map(|part| answer.and_then(|a| a.part(part)).map(String::from)),
+        }
+    }
+
+    fn stars(&self) -> usize {
+        self.parts
+            .iter()
+            .filter(|&&part| self.answers[usize::from(part) - 1].is_some())
+            .count()
+    }
+
+    fn tests_label(&self) -> String {
+        if !self.has_bin {
+            return "-".into();
+        }
+        match self.unset_tests.as_slice() {
+            [] => "✓".into(),
+            [part] if self.parts.len() > 1 => format!("todo {part}"),
+            _ => "todo".into(),
+        }
+    }
+
+    fn stars_label(&self) -> String {
+        self.parts
+            .iter()
+            .map(|&part| {
+                if self.answers[usize::from(part) - 1].is_some() {
+                    '★'
+                } else {
+                    '☆'
+                }
+            })
+            .collect()
+    }
+}
+
+fn is_non_empty(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.len() > 0)
+}
+
+pub fn handle(year: Year, json: bool) {
+    let answers = Answers::read_from_file(year).unwrap_or_else(|e| {
+        eprintln!("Warning: {e}");
+        Answers::default()
+    });
+    let timings = Timings::read_from_file(year);
+
+    let days: Vec<DayStatus> = all_days(year)
+        .map(|day| DayStatus::collect(year, day, &answers, &timings))
+        .collect();
+
+    if json {
+        let value = status_to_json(year, &days);
+        println!("{}", value.stringify().unwrap());
+    } else {
+        print!("{}", format_table(&days));
+
+        let stars: usize = days.iter().map(DayStatus::stars).sum();
+        let possible: usize = days.iter().map(|d| d.parts.len()).sum();
+        println!();
+        println!("{ANSI_BOLD}Stars:{ANSI_RESET} {stars} of {possible}");
+    }
+}
+
+fn format_table(days: &[DayStatus]) -> String {
+    let check = |value: bool| if value { "✓" } else { "-" };
+    let timing = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".into());
+
+    let mut table = String::new();
+    table.push_str(
+        "| Day | Bin | Input | Example | Puzzle | Tests  | Part 1   | Part 2   | Stars |\n",
+    );
+
+    for status in days {
+        table.push_str(&format!(
+            "| {}  | {:<3} | {:<5} | {:<7} | {:<6} | {:<6} | {:<8} | {:<8} | {:<5} |\n",
+            status.day,
+            check(status.has_bin),
+            check(status.has_input),
+            check(status.has_example),
+            check(status.has_puzzle),
+            status.tests_label(),
+            timing(&status.timings[0]),
+            timing(&status.timings[1]),
+            status.stars_label(),
+        ));
+    }
+
+    table
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn status_to_json(year: Year, days: &[DayStatus]) -> JsonValue {
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+    map.insert("year".into(), JsonValue::Number(year.into_inner().into()));
+    #[allow(clippy::cast_precision_loss)]
+    map.insert(
+        "stars".into(),
+        JsonValue::Number(days.iter().map(DayStatus::stars).sum::<usize>() as f64),
+    );
+    map.insert(
+        "days".into(),
+        JsonValue::Array(days.iter().map(JsonValue::from).collect()),
+    );
+
+    JsonValue::Object(map)
+}
+
+impl From<&DayStatus> for JsonValue {
+    fn from(value: &DayStatus) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        let optional =
+            |value: &Option<String>| value.clone().map_or(JsonValue::Null, JsonValue::String);
+        let parts = |parts: &[u8]| {
+            JsonValue::Array(
+                parts
+                    .iter()
+                    .map(|&part| JsonValue::Number(part.into()))
+                    .collect(),
+            )
+        };
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("bin".into(), JsonValue::Boolean(value.has_bin));
+        map.insert("input".into(), JsonValue::Boolean(value.has_input));
+        map.insert("example".into(), JsonValue::Boolean(value.has_example));
+        map.insert("puzzle".into(), JsonValue::Boolean(value.has_puzzle));
+        map.insert("unset_tests".into(), parts(&value.unset_tests));
+        map.insert("part_1_timing".into(), optional(&value.timings[0]));
+        map.insert("part_2_timing".into(), optional(&value.timings[1]));
+        map.insert("part_1_answer".into(), optional(&value.answers[0]));
+        map.insert("part_2_answer".into(), optional(&value.answers[1]));
+        #[allow(clippy::cast_precision_loss)]
+        map.insert("stars".into(), JsonValue::Number(value.stars() as f64));
+
+        JsonValue::Object(map)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::HashMap;
+
+    use tinyjson::JsonValue;
+
+    use super::{format_table, status_to_json, DayStatus};
+    use crate::template::Calendar;
+    use crate::{day, year};
+
+    fn status(day: u8) -> DayStatus {
+        let day = crate::template::Day::new(day).unwrap();
+        DayStatus {
+            day,
+            parts: Calendar::of(year!(2024)).parts(day),
+            has_bin: true,
+            has_input: true,
+            has_example: false,
+            has_puzzle: true,
+            unset_tests: vec![2],
+            timings: [Some("41.0µs".into()), None],
+            answers: [Some("11".into()), None],
+        }
+    }
+
+    #[test]
+    fn formats_table() {
+        let mut last = status(25);
+        last.unset_tests = vec![];
+        last.has_bin = false;
+
+        assert_eq!(
+            format_table(&[status(1), last]),
+            "| Day | Bin | Input | Example | Puzzle | Tests  | Part 1   | Part 2   | Stars |\n\
+             | 01  | ✓   | ✓     | -       | ✓      | todo 2 | 41.0µs   | -        | ★☆    |\n\
+             | 25  | -   | ✓     | -       | ✓      | -      | 41.0µs   | -        | ★     |\n"
+        );
+    }
+
+    #[test]
+    fn labels_tests_and_stars() {
+        let mut status = status(2);
+        status.unset_tests = vec![1, 2];
+        assert_eq!(status.tests_label(), "todo");
+
+        status.answers = [Some("1".into()), Some("2".into())];
+        assert_eq!(status.stars(), 2);
+        assert_eq!(status.stars_label(), "★★");
+
+        // the last day of an event only has one part.
+        let last = DayStatus {
+            parts: Calendar::of(year!(2024)).parts(day!(25)),
+            ..status
+        };
+        assert_eq!(last.stars(), 1);
+        assert_eq!(last.stars_label(), "★");
+    }
+
+    #[test]
+    fn converts_to_json() {
+        let json = status_to_json(year!(2024), &[status(1)]);
+        let map = json.get::<HashMap<String, JsonValue>>().unwrap();
+
+        assert_eq!(map["year"], JsonValue::Number(2024.0));
+        assert_eq!(map["stars"], JsonValue::Number(1.0));
+
+        let days = map["days"].get::<Vec<JsonValue>>().unwrap();
+        let day = days[0].get::<HashMap<String, JsonValue>>().unwrap();
+        assert_eq!(day["day"], JsonValue::String("01".into()));
+        assert_eq!(day["example"], JsonValue::Boolean(false));
+        assert_eq!(
+            day["unset_tests"],
+            JsonValue::Array(vec![JsonValue::Number(2.0)])
+        );
+        assert_eq!(day["part_1_timing"], JsonValue::String("41.0µs".into()));
+        assert_eq!(day["part_2_answer"], JsonValue::Null);
+    }
+}
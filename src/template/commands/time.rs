@@ -1,34 +1,101 @@
 use std::collections::HashSet;
+use std::path::Path;
+use std::process;
+use std::time::Duration;
 
-use crate::template::run_multi::run_multi;
+use crate::template::bench::BenchConfig;
+use crate::template::history::{format_report, History, RunInfo};
+use crate::template::html_report;
+use crate::template::memory::format_usage;
+use crate::template::regressions::{compare, Comparison};
+use crate::template::run_multi::child_commands::run_heap_profile;
+use crate::template::run_multi::{run_multi, Timeouts};
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days, readme_benchmarks, Day, Year, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
-    let stored_timings = Timings::read_from_file();
+/// Checks to run against the fresh timings.
+#[derive(Clone, Debug, Default)]
+pub struct Checks {
+    /// Compare with the stored timings, flagging changes above this relative threshold.
+    pub threshold: Option<f64>,
+    /// Maximum total runtime of all solutions.
+    pub budget: Option<Duration>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    year: Year,
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    memory: bool,
+    report: Option<&Path>,
+    bench: &BenchConfig,
+    checks: &Checks,
+    jobs: usize,
+    timeouts: &Timeouts,
+) {
+    let stored_timings = Timings::read_from_file(year);
 
     let days_to_run = day.map_or_else(
         || {
             if run_all {
-                all_days().collect()
+                all_days(year).collect()
             } else {
                 // when the `--all` flag is not set, filter out days that are fully benched.
-                all_days()
-                    .filter(|day| !stored_timings.is_day_complete(*day))
+                all_days(year)
+                    .filter(|day| !stored_timings.is_day_complete(year, *day))
                     .collect()
             }
         },
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let run = run_multi(year, &days_to_run, true, Some(bench), jobs, timeouts);
+    let mut timings = run.timings.unwrap();
+
+    if memory {
+        measure_heap_usage(year, &mut timings);
+    }
+
+    let merged_timings = stored_timings.merge(&timings);
+
+    // failed parts and crashed days were listed by `run_multi`, but should fail the command too.
+    let mut failed = run.has_failures;
+
+    if let Some(threshold) = checks.threshold {
+        let comparison = compare(&stored_timings, &timings, threshold);
+        print_comparison(&comparison, threshold);
+        failed |= comparison.has_regressions();
+    }
+
+    if let Some(budget) = checks.budget {
+        // days that were not run in this invocation count with their stored times.
+        let total_millis = merged_timings.total_millis();
+        let budget_millis = budget.as_secs_f64() * 1000.0;
+
+        println!();
+        if total_millis > budget_millis {
+            eprintln!(
+                "Total runtime of {total_millis:.2}ms exceeds the budget of {budget_millis:.2}ms."
+            );
+            failed = true;
+        } else {
+            println!("Total runtime of {total_millis:.2}ms is within the budget of {budget_millis:.2}ms.");
+        }
+    }
 
     if store {
-        let merged_timings = stored_timings.merge(&timings);
-        merged_timings.store_file().unwrap();
+        merged_timings.store_file(year).unwrap();
+
+        if !timings.data.is_empty() {
+            if let Err(e) = History::append(year, RunInfo::collect(), &timings) {
+                eprintln!("Failed to append to timing history: {e}");
+            }
+        }
 
         println!();
-        match readme_benchmarks::update(merged_timings) {
+        match readme_benchmarks::update(year, merged_timings.clone()) {
             Ok(()) => {
                 println!("Stored updated benchmarks.");
             }
@@ -37,4 +104,94 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
             }
         }
     }
+
+    if let Some(path) = report {
+        let history = History::read_from_file(year).unwrap_or_else(|e| {
+            eprintln!("Warning: {e}");
+            History::default()
+        });
+        match html_report::write(path, year, &merged_timings, &history) {
+            Ok(()) => println!("Wrote benchmark report to {}.", path.display()),
+            Err(e) => eprintln!("Failed to write benchmark report: {e}"),
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+/// Runs the benched days once more with heap profiling and adds their heap usage to the timings.
+/// Profiling slows down allocations, so this happens in a separate build that is not timed.
+fn measure_heap_usage(year: Year, timings: &mut Timings) {
+    println!("\n{ANSI_BOLD}Heap usage{ANSI_RESET}");
+
+    for timing in &mut timings.data {
+        let reports = match run_heap_profile(year, timing.day) {
+            Ok(reports) => reports,
+            Err(e) => {
+                eprintln!("Failed to profile day {}: {e:?}", timing.day);
+                continue;
+            }
+        };
+
+        for report in &reports.parts {
+            let Some(memory) = report.memory else {
+                continue;
+            };
+
+            println!(
+                "Day {} part {}: {ANSI_ITALIC}{}{ANSI_RESET}",
+                timing.day,
+                report.part,
+                format_usage(&memory)
+            );
+
+            match report.part {
+                1 => timing.part_1_memory = Some(memory),
+                2 => timing.part_2_memory = Some(memory),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Prints how the timings of a day changed across stored runs.
+pub fn history(year: Year, day: Day) {
+    println!("{ANSI_BOLD}Day {day} ({year}){ANSI_RESET}");
+    println!("------");
+    match History::read_from_file(year) {
+        Ok(history) => println!("{}", format_report(day, &history)),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+fn print_comparison(comparison: &Comparison, threshold: f64) {
+    println!();
+    println!(
+        "{ANSI_BOLD}Compared with stored timings{ANSI_RESET} (threshold {:.0}%)",
+        threshold * 100.0
+    );
+
+    if comparison.regressions.is_empty() && comparison.improvements.is_empty() {
+        println!("No significant changes.");
+        return;
+    }
+
+    if !comparison.improvements.is_empty() {
+        println!("Improvements:");
+        for change in &comparison.improvements {
+            println!("  {change}");
+        }
+    }
+
+    if !comparison.regressions.is_empty() {
+        println!("Regressions:");
+        for change in &comparison.regressions {
+            println!("  {change}");
+        }
+    }
 }
@@ -0,0 +1,120 @@
+use std::{io, process};
+
+use crate::template::answers::Answers;
+use crate::template::input::InputSource;
+use crate::template::run_multi::child_commands::{find_answer, run_solution};
+use crate::template::run_multi::Timeouts;
+use crate::template::{all_days, data_dir, Year, ANSI_BOLD, ANSI_RESET};
+
+enum Status {
+    Pass,
+    Fail {
+        expected: String,
+        actual: Option<String>,
+    },
+    Unknown,
+}
+
+impl Status {
+    fn new(expected: Option<&str>, actual: Option<String>) -> Self {
+        match expected {
+            None => Status::Unknown,
+            Some(expected) if actual.as_deref() == Some(expected) => Status::Pass,
+            Some(expected) => Status::Fail {
+                expected: expected.into(),
+                actual,
+            },
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Fail { .. } => "FAIL",
+            Status::Unknown => "-",
+        }
+    }
+}
+
+pub fn handle(year: Year, is_release: bool) {
+    let answers = match Answers::read_from_file(year) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        }
+    };
+    let days: Vec<_> = all_days(year)
+        .filter(|day| answers.solved_days().any(|d| d == *day))
+        .collect();
+
+    if days.is_empty() {
+        println!(
+            "No stored answers found in \"{}\".",
+            data_dir(year).join("answers.json").display()
+        );
+        return;
+    }
+
+    let mut rows = vec![];
+
+    for day in days {
+        println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+        println!("------");
+
+        let reports = run_solution(
+            year,
+            day,
+            None,
+            is_release,
+            &Timeouts::default(),
+            &InputSource::Puzzle,
+            true,
+            &mut io::stdout(),
+        )
+        .unwrap_or_default();
+        let expected = answers.get(day);
+
+        let statuses = [1, 2].map(|part| {
+            Status::new(
+                expected.and_then(|a| a.part(part)),
+                find_answer(&reports.parts, part),
+            )
+        });
+
+        println!();
+        rows.push((day, statuses));
+    }
+
+    println!("{ANSI_BOLD}Verification{ANSI_RESET}");
+    println!("------------");
+    println!("| Day | Part 1 | Part 2 |");
+
+    let mut failures = vec![];
+
+    for (day, statuses) in &rows {
+        println!(
+            "| {day}  | {:<6} | {:<6} |",
+            statuses[0].label(),
+            statuses[1].label()
+        );
+
+        for (i, status) in statuses.iter().enumerate() {
+            if let Status::Fail { expected, actual } = status {
+                failures.push(format!(
+                    "Day {day} part {}: expected {expected}, got {}",
+                    i + 1,
+                    actual.as_deref().unwrap_or("no answer")
+                ));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!();
+        for failure in &failures {
+            eprintln!("{failure}");
+        }
+        process::exit(1);
+    }
+}
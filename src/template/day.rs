@@ -2,13 +2,13 @@ use std::error::Error;
 use std::fmt::Display;
 use std::str::FromStr;
 
-#[cfg(feature = "today")]
-use chrono::{Datelike, FixedOffset, Utc};
+use crate::template::{Calendar, Year};
 
 #[cfg(feature = "today")]
-const SERVER_UTC_OFFSET: i32 = -5;
+use chrono::Utc;
 
 /// A valid day number of advent (i.e. an integer in range 1 to 25).
+/// Whether an event has a puzzle on a day depends on its [`Calendar`].
 ///
 /// # Display
 /// This value displays as a two digit number.
@@ -45,15 +45,9 @@ impl Day {
 
 #[cfg(feature = "today")]
 impl Day {
-    /// Returns the current day if it's between the 1st and the 25th of december, `None` otherwise.
+    /// Returns the current day if a puzzle of this year's event has unlocked today, `None` otherwise.
     pub fn today() -> Option<Self> {
-        let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
-        let today = Utc::now().with_timezone(&offset);
-        if today.month() == 12 && today.day() <= 25 {
-            Self::new(u8::try_from(today.day()).ok()?)
-        } else {
-            None
-        }
+        Calendar::of(Year::today()?).unlocked_at(Utc::now().timestamp())
     }
 }
 
@@ -100,20 +94,23 @@ impl Display for DayFromStrError {
 
 /* -------------------------------------------------------------------------- */
 
-/// An iterator that yields every day of advent from the 1st to the 25th.
-pub fn all_days() -> AllDays {
-    AllDays::new()
+/// An iterator that yields every day of an event, from the 1st to its last day.
+pub fn all_days(year: Year) -> AllDays {
+    AllDays::new(year)
 }
 
-/// An iterator that yields every day of advent from the 1st to the 25th.
+/// An iterator that yields every day of an event, from the 1st to its last day.
 pub struct AllDays {
     current: u8,
+    last: u8,
 }
 
 impl AllDays {
-    #[allow(clippy::new_without_default)]
-    pub fn new() -> Self {
-        Self { current: 1 }
+    pub fn new(year: Year) -> Self {
+        Self {
+            current: 1,
+            last: Calendar::of(year).days,
+        }
     }
 }
 
@@ -121,10 +118,10 @@ impl Iterator for AllDays {
     type Item = Day;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current > 25 {
+        if self.current > self.last {
             return None;
         }
-        // NOTE: the iterator starts at 1 and we have verified that the value is not above 25.
+        // NOTE: the iterator starts at 1 and no event lasts longer than 25 days.
         let day = Day(self.current);
         self.current += 1;
 
@@ -135,8 +132,20 @@ impl Iterator for AllDays {
 /* -------------------------------------------------------------------------- */
 
 /// Creates a [`Day`] value in a const context.
+/// When passed a [`Year`] first, the day is checked against the [`Calendar`] of that year.
 #[macro_export]
 macro_rules! day {
+    ($year:expr, $day:expr) => {{
+        const _ASSERT: () = assert!(
+            $day != 0 && $day <= $crate::template::Calendar::of($year).days,
+            concat!(
+                "invalid day number `",
+                $day,
+                "`, the event of this year does not have a puzzle on that day"
+            ),
+        );
+        $crate::template::Day::__new_unchecked($day)
+    }};
     ($day:expr) => {{
         const _ASSERT: () = assert!(
             $day != 0 && $day <= 25,
@@ -155,10 +164,11 @@ macro_rules! day {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{all_days, Day};
+    use crate::year;
 
     #[test]
     fn all_days_iterator() {
-        let mut iter = all_days();
+        let mut iter = all_days(year!(2024));
 
         assert_eq!(iter.next(), Some(Day(1)));
         assert_eq!(iter.next(), Some(Day(2)));
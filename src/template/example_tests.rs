@@ -0,0 +1,292 @@
+/// Tests that run the parts of a day on its example files, see [`example_tests!`](crate::example_tests).
+use std::fmt::Debug;
+use std::fs;
+use std::path::Path;
+
+use crate::template::input::InputSource;
+use crate::template::runner::{Outcome, PartOutput};
+use crate::template::{Day, Year};
+
+/// Generates a test per part that runs the part on its example files and compares the answers.
+///
+/// Every part lists `(example, expected)` pairs. An example of `None` reads
+/// `data/<year>/examples/NN.txt` and `Some(n)` reads `NN-n.txt`. An expected answer of `None`
+/// checks that the part is not solved yet. All examples of a part are checked before the test
+/// fails, and the failure message names the example file and part of every wrong answer.
+///
+/// Days that implement the [`Solution`](crate::template::Solution) trait pass their type first.
+///
+/// ```ignore
+/// #[cfg(test)]
+/// mod tests {
+///     use super::*;
+///
+///     advent_of_code::example_tests! {
+///         1 => [(None, 11)],
+///         2 => [(None, 31), (Some(2), 48)],
+///     }
+/// }
+///
+/// // or, for a day implementing `Solution`:
+/// advent_of_code::example_tests! { Day01;
+///     1 => [(None, 11)],
+/// }
+/// ```
+#[macro_export]
+macro_rules! example_tests {
+    (@expected None) => {
+        None
+    };
+    (@expected $($expected:tt)+) => {
+        Some($($expected)+)
+    };
+
+    (@test 1, $root:expr, $solve:expr, $examples:expr) => {
+        #[test]
+        fn test_part_one() {
+            let root = $root;
+            $crate::template::example_tests::check_examples(
+                ::std::convert::AsRef::as_ref(&root), YEAR, DAY, 1, &$examples, $solve,
+            );
+        }
+    };
+    (@test 2, $root:expr, $solve:expr, $examples:expr) => {
+        #[test]
+        fn test_part_two() {
+            let root = $root;
+            $crate::template::example_tests::check_examples(
+                ::std::convert::AsRef::as_ref(&root), YEAR, DAY, 2, &$examples, $solve,
+            );
+        }
+    };
+
+    (@solve 1) => {
+        part_one
+    };
+    (@solve 2) => {
+        part_two
+    };
+    (@solve 1, $solution:ty) => {
+        |input: &str| {
+            use $crate::template::Solution;
+            <$solution>::part_one(&<$solution>::parse(input))
+        }
+    };
+    (@solve 2, $solution:ty) => {
+        |input: &str| {
+            use $crate::template::Solution;
+            <$solution>::part_two(&<$solution>::parse(input))
+        }
+    };
+
+    // reads the example files relative to `$root` instead of the working directory.
+    (@in $root:expr; $($part:tt => [$(($example:expr, $($expected:tt)+)),* $(,)?]),* $(,)?) => {
+        $(
+            $crate::example_tests!(
+                @test $part,
+                $root,
+                $crate::example_tests!(@solve $part),
+                [$(($example, $crate::example_tests!(@expected $($expected)+))),*]
+            );
+        )*
+    };
+    (@in $root:expr; $solution:ty; $($part:tt => [$(($example:expr, $($expected:tt)+)),* $(,)?]),* $(,)?) => {
+        $(
+            $crate::example_tests!(
+                @test $part,
+                $root,
+                $crate::example_tests!(@solve $part, $solution),
+                [$(($example, $crate::example_tests!(@expected $($expected)+))),*]
+            );
+        )*
+    };
+
+    ($($part:tt => [$(($example:expr, $($expected:tt)+)),* $(,)?]),* $(,)?) => {
+        $crate::example_tests!(@in ""; $($part => [$(($example, $($expected)+)),*]),*);
+    };
+    ($solution:ty; $($part:tt => [$(($example:expr, $($expected:tt)+)),* $(,)?]),* $(,)?) => {
+        $crate::example_tests!(@in ""; $solution; $($part => [$(($example, $($expected)+)),*]),*);
+    };
+}
+
+/// Runs a part on its example files, read relative to `root`, and panics if any answer differs from the expected one.
+/// Used by the tests that [`example_tests!`](crate::example_tests) generates.
+///
+/// # Panics
+/// Panics if an example can't be read or an answer is wrong.
+#[track_caller]
+pub fn check_examples<'a, T: PartialEq + Debug, O: PartOutput<'a, Answer = T>>(
+    root: &Path,
+    year: Year,
+    day: Day,
+    part: u8,
+    examples: &[(Option<u8>, Option<T>)],
+    solve: impl Fn(&str) -> O,
+) {
+    let failures: Vec<String> = examples
+        .iter()
+        .filter_map(|(example, expected)| {
+            let path = InputSource::Example(*example)
+                .path(year, day)
+                .unwrap_or_default();
+
+            let input = match fs::read_to_string(root.join(&path)) {
+                Ok(input) => input,
+                Err(e) => return Some(format!("{}: could not read example: {e}", path.display())),
+            };
+
+            let result = match solve(&input).into_outcome() {
+                Outcome::Solved(answer) => Some(answer),
+                Outcome::Unsolved => None,
+                Outcome::Failed(error) => {
+                    return Some(format!(
+                        "{}, part {part}: failed with {}",
+                        path.display(),
+                        error.join(": ")
+                    ))
+                }
+            };
+
+            (&result != expected).then(|| {
+                format!(
+                    "{}, part {part}: expected {expected:?}, got {result:?}",
+                    path.display()
+                )
+            })
+        })
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} examples failed:\n{}",
+        failures.len(),
+        examples.len(),
+        failures.join("\n")
+    );
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::{env, fs};
+
+    use super::check_examples;
+    use crate::template::{Day, Year};
+    use crate::{day, year};
+
+    const YEAR: Year = year!(2024);
+    const DAY: Day = day!(1);
+
+    fn part_one(input: &str) -> Option<usize> {
+        Some(input.lines().count())
+    }
+
+    fn part_two(_input: &str) -> Option<usize> {
+        None
+    }
+
+    /// A data directory with a three line example for day 01, removed once dropped.
+    struct Fixture(PathBuf);
+
+    impl AsRef<Path> for Fixture {
+        fn as_ref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn fixture() -> Fixture {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let id = COUNT.fetch_add(1, Ordering::Relaxed);
+        let root = env::temp_dir().join(format!("aoc-examples-{}-{id}", std::process::id()));
+
+        fs::create_dir_all(root.join("data/2024/examples")).unwrap();
+        fs::write(root.join("data/2024/examples/01.txt"), "a\nb\nc\n").unwrap();
+        Fixture(root)
+    }
+
+    mod generated {
+        use super::*;
+
+        crate::example_tests! { @in fixture();
+            1 => [(None, 3)],
+            2 => [(None, None)],
+        }
+    }
+
+    struct Lines;
+
+    impl crate::template::Solution for Lines {
+        type Parsed = usize;
+        type Answer1 = usize;
+        type Answer2 = usize;
+
+        fn parse(input: &str) -> Self::Parsed {
+            input.lines().count()
+        }
+
+        fn part_one(parsed: &Self::Parsed) -> Option<usize> {
+            Some(*parsed)
+        }
+
+        fn part_two(_parsed: &Self::Parsed) -> Option<usize> {
+            None
+        }
+    }
+
+    mod generated_for_solution {
+        use super::*;
+
+        crate::example_tests! { @in fixture(); Lines;
+            1 => [(None, 3)],
+            2 => [(None, None)],
+        }
+    }
+
+    #[test]
+    fn checks_answers() {
+        let root = fixture();
+        check_examples(root.as_ref(), YEAR, DAY, 1, &[(None, Some(3))], part_one);
+        check_examples(root.as_ref(), YEAR, DAY, 2, &[(None, None)], part_two);
+    }
+
+    #[test]
+    #[should_panic(expected = "data/2024/examples/01.txt, part 1: expected Some(2), got Some(3)")]
+    fn names_failing_example() {
+        let root = fixture();
+        check_examples(
+            root.as_ref(),
+            YEAR,
+            DAY,
+            1,
+            &[(None, Some(3)), (None, Some(2))],
+            part_one,
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "data/2024/examples/01.txt, part 2: failed with no rules: empty input"
+    )]
+    fn names_failed_example() {
+        let root = fixture();
+        check_examples(root.as_ref(), YEAR, DAY, 2, &[(None, Some(1))], |_| {
+            Err::<usize, _>("no rules: empty input")
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "data/2024/examples/01-9.txt: could not read example")]
+    fn names_missing_example() {
+        let root = fixture();
+        check_examples(root.as_ref(), YEAR, DAY, 2, &[(Some(9), Some(1))], part_one);
+    }
+}
@@ -0,0 +1,465 @@
+/// An append-only log of benchmark runs, used to follow how solutions change over time.
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{Error, ErrorKind},
+    path::Path,
+    process::Command,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tinyjson::JsonValue;
+
+use crate::template::regressions::Step;
+use crate::template::timings::{Timing, Timings};
+use crate::template::{data_dir, Day, Year};
+
+/// Name of the file inside the data directory of a year.
+static HISTORY_FILE_NAME: &str = "timings_history.json";
+
+/// Environment variable that overrides the machine identifier stored with a run.
+const MACHINE_ENV: &str = "AOC_MACHINE";
+
+/// Where, when and with which toolchain a benchmark was run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunInfo {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    /// Short hash of the checked out commit, suffixed with `-dirty` for uncommitted changes.
+    pub commit: Option<String>,
+    pub rustc: Option<String>,
+    pub machine: Option<String>,
+}
+
+impl RunInfo {
+    /// Collects information about the current environment.
+    pub fn collect() -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        let commit = command_output("git", &["rev-parse", "--short", "HEAD"]).map(|hash| {
+            let is_dirty =
+                command_output("git", &["status", "--porcelain", "--untracked-files=no"])
+                    .is_some_and(|s| !s.is_empty());
+            if is_dirty {
+                format!("{hash}-dirty")
+            } else {
+                hash
+            }
+        });
+
+        let rustc = command_output("rustc", &["--version"])
+            .map(|v| v.trim_start_matches("rustc ").to_string());
+
+        let machine = env::var(MACHINE_ENV)
+            .ok()
+            .or_else(|| fs::read_to_string("/etc/hostname").ok())
+            .or_else(|| command_output("hostname", &[]))
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        Self {
+            timestamp,
+            commit,
+            rustc,
+            machine,
+        }
+    }
+}
+
+/// Trimmed stdout of a successful command.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// A single `cargo time --store` run.
+#[derive(Clone, Debug)]
+pub struct HistoryEntry {
+    pub info: RunInfo,
+    pub timings: Vec<Timing>,
+}
+
+/// Represents all recorded benchmark runs.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    pub data: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// Dehydrate history to the JSON file of a year.
+    pub fn store_file(&self, year: Year) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        fs::create_dir_all(data_dir(year))?;
+        let mut file = fs::File::create(data_dir(year).join(HISTORY_FILE_NAME))?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate history from the JSON file of a year. If not present, returns an empty history.
+    pub fn read_from_file(year: Year) -> Result<Self, String> {
+        Self::read_from_path(&data_dir(year).join(HISTORY_FILE_NAME))
+    }
+
+    fn read_from_path(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(json) => History::try_from(json),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.to_string()),
+        }
+        .map_err(|e| format!("could not read {}: {e}", path.display()))
+    }
+
+    /// Append a run to the history file of a year.
+    /// Refuses to if the existing file can't be read, as rewriting it would lose earlier runs.
+    pub fn append(year: Year, info: RunInfo, timings: &Timings) -> Result<(), String> {
+        let mut history = Self::read_from_file(year)?;
+        history.data.push(HistoryEntry {
+            info,
+            timings: timings.data.clone(),
+        });
+        history.store_file(year).map_err(|e| e.to_string())
+    }
+
+    /// All recorded timings of a day, oldest first.
+    pub fn for_day(&self, day: Day) -> Vec<(&RunInfo, &Timing)> {
+        let mut runs: Vec<_> = self
+            .data
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .timings
+                    .iter()
+                    .find(|t| t.day == day)
+                    .map(|timing| (&entry.info, timing))
+            })
+            .collect();
+        runs.sort_by_key(|(info, _)| info.timestamp);
+        runs
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Formats the trend of a day as a table with one row per run, followed by a summary per part.
+pub fn format_report(day: Day, history: &History) -> String {
+    let runs = history.for_day(day);
+
+    if runs.is_empty() {
+        return format!(
+            "No history recorded for day {day}. Use `cargo time {day} --store` to record one."
+        );
+    }
+
+    let has_parse = runs.iter().any(|(_, t)| t.parse.is_some());
+    let steps: Vec<Step> = if has_parse {
+        vec![Step::Parse, Step::Part(1), Step::Part(2)]
+    } else {
+        vec![Step::Part(1), Step::Part(2)]
+    };
+
+    let mut header = "| Date | Commit | rustc | Machine |".to_string();
+    let mut separator = "| :--- | :--- | :--- | :--- |".to_string();
+    for step in &steps {
+        header.push_str(&format!(" {} |", capitalize(&step.to_string())));
+        separator.push_str(" ---: |");
+    }
+
+    let mut lines = vec![header, separator];
+    let mut previous: Vec<Option<f64>> = vec![None; steps.len()];
+
+    for (info, timing) in &runs {
+        let mut line = format!(
+            "| {} | {} | {} | {} |",
+            format_timestamp(info.timestamp),
+            info.commit.as_deref().unwrap_or("-"),
+            info.rustc
+                .as_deref()
+                .and_then(|v| v.split_whitespace().next())
+                .unwrap_or("-"),
+            info.machine.as_deref().unwrap_or("-"),
+        );
+
+        for (i, step) in steps.iter().enumerate() {
+            let cell = match step.nanos(timing) {
+                Some(nanos) => {
+                    let cell = match previous[i] {
+                        Some(prev) if prev > 0.0 => {
+                            format!("`{}` ({:+.1}%)", format_nanos(nanos), change(prev, nanos))
+                        }
+                        _ => format!("`{}`", format_nanos(nanos)),
+                    };
+                    previous[i] = Some(nanos);
+                    cell
+                }
+                None => "-".into(),
+            };
+            line.push_str(&format!(" {cell} |"));
+        }
+
+        lines.push(line);
+    }
+
+    lines.push(String::new());
+
+    for step in &steps {
+        let values: Vec<f64> = runs.iter().filter_map(|(_, t)| step.nanos(t)).collect();
+
+        let summary = match (values.first(), values.last()) {
+            (Some(first), Some(last)) if values.len() > 1 && *first > 0.0 => format!(
+                "{} → {} ({:+.1}%) over {} runs",
+                format_nanos(*first),
+                format_nanos(*last),
+                change(*first, *last),
+                values.len()
+            ),
+            (Some(first), _) => format!("{} in a single run", format_nanos(*first)),
+            _ => "not solved".into(),
+        };
+
+        lines.push(format!("{}: {summary}", capitalize(&step.to_string())));
+    }
+
+    lines.join("\n")
+}
+
+fn change(old: f64, new: f64) -> f64 {
+    (new - old) / old * 100.0
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_nanos(nanos: f64) -> String {
+    format!("{:.1?}", Duration::from_nanos(nanos as u64))
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+/// Formats a unix timestamp as `YYYY-MM-DD HH:MM` (UTC).
+#[allow(clippy::cast_possible_wrap)]
+pub(crate) fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let secs = timestamp % 86_400;
+
+    // see: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60
+    )
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<History> for JsonValue {
+    fn from(value: History) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for History {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(History {
+            data: json_data
+                .iter()
+                .map(HistoryEntry::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&HistoryEntry> for JsonValue {
+    fn from(value: &HistoryEntry) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        let optional = |s: &Option<String>| s.clone().map_or(JsonValue::Null, JsonValue::String);
+
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(value.info.timestamp as f64),
+        );
+        map.insert("commit".into(), optional(&value.info.commit));
+        map.insert("rustc".into(), optional(&value.info.rustc));
+        map.insert("machine".into(), optional(&value.info.machine));
+        map.insert(
+            "timings".into(),
+            JsonValue::Array(value.timings.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for HistoryEntry {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected history entry to be a JSON object.")?;
+
+        let timestamp = json
+            .get("timestamp")
+            .and_then(|v| v.get::<f64>())
+            .map(|v| *v as u64)
+            .ok_or("Expected entry.timestamp to be a number.")?;
+
+        let optional = |key: &str| json.get(key).and_then(|v| v.get::<String>()).cloned();
+
+        let timings = json
+            .get("timings")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("Expected entry.timings to be an array.")?
+            .iter()
+            .map(Timing::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(HistoryEntry {
+            info: RunInfo {
+                timestamp,
+                commit: optional("commit"),
+                rustc: optional("rustc"),
+                machine: optional("machine"),
+            },
+            timings,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{env, fs};
+
+    use super::{format_report, format_timestamp, History, HistoryEntry, RunInfo};
+    use crate::day;
+    use crate::template::timings::Timing;
+
+    fn entry(timestamp: u64, commit: &str, part_1: &str, part_2: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            info: RunInfo {
+                timestamp,
+                commit: Some(commit.into()),
+                rustc: Some("1.83.0 (90b35a623 2024-11-26)".into()),
+                machine: None,
+            },
+            timings: vec![Timing {
+                part_1: Some(part_1.into()),
+                part_2: part_2.map(Into::into),
+                ..Timing::new(day!(1))
+            }],
+        }
+    }
+
+    #[test]
+    fn formats_timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1_733_029_200), "2024-12-01 05:00");
+        assert_eq!(format_timestamp(1_709_208_000), "2024-02-29 12:00");
+    }
+
+    #[test]
+    fn round_trips_history() {
+        let history = History {
+            data: vec![entry(1_733_029_200, "abc1234", "1.0ms", None)],
+        };
+
+        let json = tinyjson::JsonValue::from(history).stringify().unwrap();
+        let parsed = History::try_from(json).unwrap();
+
+        assert_eq!(parsed.data.len(), 1);
+        assert_eq!(parsed.data[0].info.commit.as_deref(), Some("abc1234"));
+        assert_eq!(parsed.data[0].info.machine, None);
+        assert_eq!(parsed.data[0].timings[0].part_1.as_deref(), Some("1.0ms"));
+    }
+
+    #[test]
+    fn reports_trends() {
+        let history = History {
+            data: vec![
+                entry(1_733_115_600, "def5678", "1.5ms", Some("4.0ms")),
+                entry(1_733_029_200, "abc1234", "2.0ms", None),
+            ],
+        };
+
+        let report = format_report(day!(1), &history);
+        let lines: Vec<&str> = report.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "| Date | Commit | rustc | Machine | Part 1 | Part 2 |"
+        );
+        assert_eq!(
+            lines[2],
+            "| 2024-12-01 05:00 | abc1234 | 1.83.0 | - | `2.0ms` | - |"
+        );
+        assert_eq!(
+            lines[3],
+            "| 2024-12-02 05:00 | def5678 | 1.83.0 | - | `1.5ms` (-25.0%) | `4.0ms` |"
+        );
+        assert_eq!(lines[5], "Part 1: 2.0ms → 1.5ms (-25.0%) over 2 runs");
+        assert_eq!(lines[6], "Part 2: 4.0ms in a single run");
+    }
+
+    #[test]
+    fn reports_missing_history() {
+        let report = format_report(day!(2), &History::default());
+        assert!(report.starts_with("No history recorded for day 02."));
+    }
+
+    #[test]
+    fn only_treats_missing_files_as_empty() {
+        let dir = env::temp_dir().join(format!("aoc-history-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("timings_history.json");
+
+        let missing = History::read_from_path(&path);
+        fs::write(&path, r#"{ "data": [{ "timings": 1 }] }"#).unwrap();
+        let malformed = History::read_from_path(&path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(missing.unwrap().data.is_empty());
+        assert!(malformed.unwrap_err().starts_with("could not read"));
+    }
+}
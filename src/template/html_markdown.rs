@@ -0,0 +1,465 @@
+/// Minimal HTML to markdown conversion for puzzle pages served by adventofcode.com.
+/// Only the small subset of tags used inside `<article>` elements is supported.
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Debug)]
+enum Node {
+    Element {
+        name: String,
+        attrs: Vec<(String, String)>,
+        children: Vec<Node>,
+    },
+    Text(String),
+}
+
+const VOID_ELEMENTS: [&str; 6] = ["br", "hr", "img", "input", "link", "meta"];
+
+/// Converts every `<article>` element found in `html` to markdown.
+/// Articles are separated by a blank line.
+#[must_use]
+pub fn articles_to_markdown(html: &str) -> String {
+    let nodes = parse(html);
+    let mut articles = vec![];
+    collect_articles(&nodes, &mut articles);
+
+    articles
+        .iter()
+        .map(|children| render_blocks(children).trim().to_string())
+        .filter(|article| !article.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn collect_articles<'a>(nodes: &'a [Node], out: &mut Vec<&'a [Node]>) {
+    for node in nodes {
+        if let Node::Element { name, children, .. } = node {
+            if name == "article" {
+                out.push(children);
+            } else {
+                collect_articles(children, out);
+            }
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn parse(html: &str) -> Vec<Node> {
+    let mut chars = html.chars().peekable();
+    let (nodes, _) = parse_children(&mut chars, &mut vec![]);
+    nodes
+}
+
+/// Parses nodes until the closing tag of the innermost element in `open` is found.
+/// Returns the parsed nodes and the name of an ancestor that was closed implicitly, if any.
+fn parse_children(
+    chars: &mut Peekable<Chars>,
+    open: &mut Vec<String>,
+) -> (Vec<Node>, Option<String>) {
+    let mut nodes = vec![];
+    let mut text = String::new();
+
+    while let Some(&c) = chars.peek() {
+        if c != '<' {
+            text.push(c);
+            chars.next();
+            continue;
+        }
+
+        chars.next();
+        let tag = read_until(chars, '>');
+
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        }
+
+        if !text.is_empty() {
+            nodes.push(Node::Text(decode_entities(&text)));
+            text.clear();
+        }
+
+        if let Some(closing) = tag.strip_prefix('/') {
+            let closing = closing.trim().to_ascii_lowercase();
+            if open.last() == Some(&closing) {
+                return (nodes, None);
+            }
+            if open.contains(&closing) {
+                return (nodes, Some(closing));
+            }
+            // ignore stray closing tags that do not match any open element.
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let (name, attrs) = parse_tag(tag.trim_end_matches('/'));
+
+        if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+            nodes.push(Node::Element {
+                name,
+                attrs,
+                children: vec![],
+            });
+            continue;
+        }
+
+        open.push(name.clone());
+        let (children, unmatched) = parse_children(chars, open);
+        open.pop();
+
+        nodes.push(Node::Element {
+            name,
+            attrs,
+            children,
+        });
+
+        if let Some(unmatched) = unmatched {
+            if open.last() == Some(&unmatched) {
+                return (nodes, None);
+            }
+            return (nodes, Some(unmatched));
+        }
+    }
+
+    if !text.is_empty() {
+        nodes.push(Node::Text(decode_entities(&text)));
+    }
+
+    (nodes, None)
+}
+
+fn read_until(chars: &mut Peekable<Chars>, end: char) -> String {
+    let mut s = String::new();
+    let mut quote: Option<char> = None;
+
+    for c in chars.by_ref() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == end => break,
+            _ => {}
+        }
+        s.push(c);
+    }
+
+    s
+}
+
+fn parse_tag(tag: &str) -> (String, Vec<(String, String)>) {
+    let tag = tag.trim();
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = tag[..name_end].to_ascii_lowercase();
+
+    let mut attrs = vec![];
+    let mut rest = tag[name_end..].trim_start();
+
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+
+        let value = if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            match after_eq.chars().next() {
+                Some(q @ ('"' | '\'')) => {
+                    let inner = &after_eq[1..];
+                    let value_end = inner.find(q).unwrap_or(inner.len());
+                    rest = inner.get(value_end + 1..).unwrap_or("").trim_start();
+                    &inner[..value_end]
+                }
+                _ => {
+                    let value_end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                    rest = after_eq[value_end..].trim_start();
+                    &after_eq[..value_end]
+                }
+            }
+        } else {
+            ""
+        };
+
+        if !key.is_empty() {
+            attrs.push((key, decode_entities(value)));
+        }
+    }
+
+    (name, attrs)
+}
+
+fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            }?;
+            Some((c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn attr<'a>(attrs: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+fn render_blocks(nodes: &[Node]) -> String {
+    let mut out = String::new();
+
+    for node in nodes {
+        match node {
+            Node::Text(text) => {
+                let text = collapse_whitespace(text);
+                if !text.trim().is_empty() {
+                    out.push_str(text.trim());
+                    out.push_str("\n\n");
+                }
+            }
+            Node::Element { name, children, .. } => match name.as_str() {
+                "h1" | "h2" | "h3" | "h4" => {
+                    let level = name[1..].parse::<usize>().unwrap_or(2);
+                    out.push_str(&"#".repeat(level));
+                    out.push(' ');
+                    out.push_str(render_inline(children).trim());
+                    out.push_str("\n\n");
+                }
+                "p" => {
+                    out.push_str(render_inline(children).trim());
+                    out.push_str("\n\n");
+                }
+                "pre" => {
+                    let mut code = String::new();
+                    render_raw(children, &mut code);
+                    if !code.ends_with('\n') {
+                        code.push('\n');
+                    }
+                    out.push_str("```\n");
+                    out.push_str(&code);
+                    out.push_str("```\n\n");
+                }
+                "ul" | "ol" => {
+                    let ordered = name == "ol";
+                    let items = children.iter().filter_map(|child| match child {
+                        Node::Element { name, children, .. } if name == "li" => Some(children),
+                        _ => None,
+                    });
+                    for (i, item) in items.enumerate() {
+                        if ordered {
+                            out.push_str(&format!("{}. ", i + 1));
+                        } else {
+                            out.push_str("- ");
+                        }
+                        out.push_str(render_inline(item).trim());
+                        out.push('\n');
+                    }
+                    out.push('\n');
+                }
+                "div" | "section" | "main" | "article" | "li" => {
+                    out.push_str(&render_blocks(children));
+                }
+                "script" | "style" => {}
+                _ => {
+                    let inline = render_inline(std::slice::from_ref(node));
+                    if !inline.trim().is_empty() {
+                        out.push_str(inline.trim());
+                        out.push_str("\n\n");
+                    }
+                }
+            },
+        }
+    }
+
+    out
+}
+
+fn render_inline(nodes: &[Node]) -> String {
+    let mut out = String::new();
+
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(&collapse_whitespace(text)),
+            Node::Element {
+                name,
+                attrs,
+                children,
+            } => match name.as_str() {
+                "em" | "strong" | "b" | "i" => {
+                    let inner = render_inline(children);
+                    if inner.trim().is_empty() {
+                        out.push_str(&inner);
+                    } else {
+                        out.push('*');
+                        out.push_str(&inner);
+                        out.push('*');
+                    }
+                }
+                "code" => {
+                    let mut code = String::new();
+                    render_raw(children, &mut code);
+                    // highlighted inline code is the usual way puzzles mark an expected answer.
+                    let highlighted = children
+                        .iter()
+                        .any(|c| matches!(c, Node::Element { name, .. } if name == "em"));
+                    if highlighted {
+                        out.push_str(&format!("*`{code}`*"));
+                    } else {
+                        out.push_str(&format!("`{code}`"));
+                    }
+                }
+                "a" => {
+                    let inner = render_inline(children);
+                    match attr(attrs, "href") {
+                        Some(href) => out.push_str(&format!("[{inner}]({href})")),
+                        None => out.push_str(&inner),
+                    }
+                }
+                "br" => out.push('\n'),
+                "script" | "style" => {}
+                _ => out.push_str(&render_inline(children)),
+            },
+        }
+    }
+
+    out
+}
+
+fn render_raw(nodes: &[Node], out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Element { name, children, .. } => {
+                if name == "br" {
+                    out.push('\n');
+                }
+                render_raw(children, out);
+            }
+        }
+    }
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut last_was_space = false;
+
+    for c in s.chars() {
+        if c.is_whitespace() {
+            if !last_was_space {
+                out.push(' ');
+            }
+            last_was_space = true;
+        } else {
+            out.push(c);
+            last_was_space = false;
+        }
+    }
+
+    out
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::articles_to_markdown;
+
+    #[test]
+    fn converts_puzzle_articles() {
+        let html = r#"<!DOCTYPE html>
+<html><body><main>
+<article class="day-desc"><h2>--- Day 1: Test ---</h2><p>Read a <a href="/2024/about">list</a>
+of <em>numbers</em>:</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<ul><li>First &amp; foremost</li><li>Then <code>x &lt; y</code></li></ul>
+<p>The answer is <code><em>11</em></code>.</p>
+</article>
+<p>Answer: <input type="text" name="answer"/></p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>Again.</p></article>
+</main></body></html>"#;
+
+        let expected = [
+            "## --- Day 1: Test ---",
+            "",
+            "Read a [list](/2024/about) of *numbers*:",
+            "",
+            "```",
+            "3   4",
+            "4   3",
+            "```",
+            "",
+            "- First & foremost",
+            "- Then `x < y`",
+            "",
+            "The answer is *`11`*.",
+            "",
+            "## --- Part Two ---",
+            "",
+            "Again.",
+        ]
+        .join("\n");
+
+        assert_eq!(articles_to_markdown(html), expected);
+    }
+
+    #[test]
+    fn ignores_content_outside_articles() {
+        let html = "<html><body><p>Nothing to see here.</p></body></html>";
+        assert_eq!(articles_to_markdown(html), "");
+    }
+
+    #[test]
+    fn converts_submission_responses() {
+        let html = r#"<main><article><p>That's the right answer!  You are <span class="day-success">one gold star</span> closer. <a href="/2024">[Return to Event Calendar]</a></p></article></main>"#;
+        assert_eq!(
+            articles_to_markdown(html),
+            "That's the right answer! You are one gold star closer. [[Return to Event Calendar]](/2024)"
+        );
+    }
+
+    #[test]
+    fn tolerates_stray_closing_tags() {
+        let html = "<article><p>Hello</span> world</p></div></article>";
+        assert_eq!(articles_to_markdown(html), "Hello world");
+    }
+}
@@ -0,0 +1,553 @@
+/// Renders benchmark timings as a self-contained HTML page with inline SVG charts.
+/// The page has no scripts or external resources, so it can be opened from disk or published as is.
+use std::{fmt::Write, fs, io, path::Path, time::Duration};
+
+use crate::template::history::History;
+use crate::template::memory::format_usage;
+use crate::template::regressions::Step;
+use crate::template::timings::{Timing, Timings};
+use crate::template::Year;
+
+const CHART_WIDTH: f64 = 760.0;
+const CHART_HEIGHT: f64 = 280.0;
+/// Space around the plot area for axis labels.
+const MARGIN_LEFT: f64 = 64.0;
+const MARGIN_BOTTOM: f64 = 28.0;
+const MARGIN_TOP: f64 = 12.0;
+
+const SPARKLINE_WIDTH: f64 = 120.0;
+const SPARKLINE_HEIGHT: f64 = 24.0;
+
+/// Bar colors of the timed steps.
+const PARSE_COLOR: &str = "#9e9e9e";
+const PART_1_COLOR: &str = "#9999cc";
+const PART_2_COLOR: &str = "#ffff66";
+
+const STYLE: &str = "body { background: #0f0f23; color: #cccccc; font-family: 'Source Code Pro', monospace; margin: 2em auto; max-width: 820px; }
+h1, h2 { color: #00cc00; font-weight: normal; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border-bottom: 1px solid #333340; padding: 4px 8px; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+svg text { fill: #cccccc; font-size: 11px; }
+.axis { stroke: #333340; }
+.legend span { display: inline-block; margin-right: 1em; }
+.swatch { display: inline-block; width: 10px; height: 10px; margin-right: 4px; }";
+
+/// Writes the report of a year to `path`.
+pub fn write(path: &Path, year: Year, timings: &Timings, history: &History) -> io::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, render(year, timings, history))
+}
+
+/// Renders the report of a year as an HTML document.
+pub fn render(year: Year, timings: &Timings, history: &History) -> String {
+    let mut html = String::new();
+
+    let _ = writeln!(html, "<!DOCTYPE html>");
+    let _ = writeln!(html, "<html lang=\"en\">");
+    let _ = writeln!(html, "<head>");
+    let _ = writeln!(html, "<meta charset=\"utf-8\">");
+    let _ = writeln!(html, "<title>Advent of Code {year} · Benchmarks</title>");
+    let _ = writeln!(html, "<style>\n{STYLE}\n</style>");
+    let _ = writeln!(html, "</head>");
+    let _ = writeln!(html, "<body>");
+    let _ = writeln!(html, "<h1>Advent of Code {year} · Benchmarks</h1>");
+
+    if timings.data.is_empty() {
+        let _ = writeln!(
+            html,
+            "<p>No timings stored yet. Use <code>cargo time --store</code> to record some.</p>"
+        );
+    } else {
+        let _ = writeln!(
+            html,
+            "<p>Total: <strong>{:.2}ms</strong> over {} days.</p>",
+            timings.total_millis(),
+            timings.data.len()
+        );
+
+        let steps = steps(timings);
+
+        let _ = writeln!(html, "<h2>Time per part</h2>");
+        let _ = writeln!(html, "{}", legend(&steps));
+        let _ = writeln!(html, "{}", bar_chart(timings, &steps));
+
+        let _ = writeln!(html, "<h2>Cumulative runtime</h2>");
+        let _ = writeln!(html, "{}", cumulative_chart(timings));
+
+        let _ = writeln!(html, "<h2>Days</h2>");
+        let _ = writeln!(html, "{}", table(timings, history, &steps));
+    }
+
+    let _ = writeln!(html, "</body>");
+    let _ = writeln!(html, "</html>");
+
+    html
+}
+
+/// Steps shown in the report. Parsing is only shown once a solution reports it separately.
+fn steps(timings: &Timings) -> Vec<Step> {
+    if timings.data.iter().any(|t| t.parse.is_some()) {
+        vec![Step::Parse, Step::Part(1), Step::Part(2)]
+    } else {
+        vec![Step::Part(1), Step::Part(2)]
+    }
+}
+
+fn step_color(step: Step) -> &'static str {
+    match step {
+        Step::Parse => PARSE_COLOR,
+        Step::Part(1) => PART_1_COLOR,
+        Step::Part(_) => PART_2_COLOR,
+    }
+}
+
+fn legend(steps: &[Step]) -> String {
+    let items: String = steps
+        .iter()
+        .map(|step| {
+            format!(
+                "<span><i class=\"swatch\" style=\"background: {}\"></i>{step}</span>",
+                step_color(*step)
+            )
+        })
+        .collect();
+    format!("<p class=\"legend\">{items}</p>")
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Maps values to the vertical position of a chart on a logarithmic scale.
+#[derive(Clone, Copy, Debug)]
+struct LogScale {
+    /// Exponents of the decades at the bottom and top of the chart.
+    min_exp: i32,
+    max_exp: i32,
+}
+
+impl LogScale {
+    /// A scale that spans whole decades around the given values.
+    /// The smallest value always gets a visible bar, even if it is a power of ten.
+    fn fit(values: impl Iterator<Item = f64>) -> Self {
+        let (min, max) = values
+            .filter(|v| *v > 0.0)
+            .fold((f64::MAX, f64::MIN), |(min, max), v| {
+                (min.min(v), max.max(v))
+            });
+
+        if min > max {
+            return Self {
+                min_exp: 0,
+                max_exp: 1,
+            };
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        let min_exp = min.log10().ceil() as i32 - 1;
+        #[allow(clippy::cast_possible_truncation)]
+        let max_exp = (max.log10().ceil() as i32).max(min_exp + 1);
+
+        Self { min_exp, max_exp }
+    }
+
+    /// Relative height of a value, from `0.0` at the bottom to `1.0` at the top.
+    fn position(self, value: f64) -> f64 {
+        if value <= 0.0 {
+            return 0.0;
+        }
+        let range = f64::from(self.max_exp - self.min_exp);
+        ((value.log10() - f64::from(self.min_exp)) / range).clamp(0.0, 1.0)
+    }
+
+    /// Values of the grid lines, one per decade.
+    fn ticks(self) -> impl Iterator<Item = f64> {
+        (self.min_exp..=self.max_exp).map(|exp| 10_f64.powi(exp))
+    }
+}
+
+/// Grouped bars of the time of every step per day, in nanoseconds on a log scale.
+fn bar_chart(timings: &Timings, steps: &[Step]) -> String {
+    let scale = LogScale::fit(
+        timings
+            .data
+            .iter()
+            .flat_map(|timing| steps.iter().filter_map(|step| step.nanos(timing))),
+    );
+
+    let plot_width = CHART_WIDTH - MARGIN_LEFT;
+    let plot_height = CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    #[allow(clippy::cast_precision_loss)]
+    let group_width = plot_width / timings.data.len() as f64;
+    #[allow(clippy::cast_precision_loss)]
+    let bar_width = (group_width * 0.8) / steps.len() as f64;
+
+    let mut svg = svg_open(
+        CHART_WIDTH,
+        CHART_HEIGHT,
+        "Time per part on a logarithmic scale",
+    );
+
+    for tick in scale.ticks() {
+        let y = MARGIN_TOP + plot_height * (1.0 - scale.position(tick));
+        let _ = writeln!(
+            svg,
+            "<line class=\"axis\" x1=\"{MARGIN_LEFT}\" y1=\"{y:.1}\" x2=\"{CHART_WIDTH}\" y2=\"{y:.1}\"/>"
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            MARGIN_LEFT - 6.0,
+            y + 4.0,
+            format_nanos(tick)
+        );
+    }
+
+    for (i, timing) in timings.data.iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        let group_x = MARGIN_LEFT + group_width * i as f64 + group_width * 0.1;
+
+        for (j, step) in steps.iter().enumerate() {
+            let Some(nanos) = step.nanos(timing) else {
+                continue;
+            };
+            let height = plot_height * scale.position(nanos);
+            #[allow(clippy::cast_precision_loss)]
+            let x = group_x + bar_width * j as f64;
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{x:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{height:.1}\" fill=\"{}\"><title>Day {} {step}: {}</title></rect>",
+                MARGIN_TOP + plot_height - height,
+                bar_width.max(1.0),
+                step_color(*step),
+                timing.day,
+                format_nanos(nanos)
+            );
+        }
+
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            group_x + group_width * 0.4,
+            CHART_HEIGHT - 8.0,
+            timing.day.into_inner()
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Running total of the time of all days, in the order of the event.
+fn cumulative_millis(timings: &Timings) -> Vec<f64> {
+    timings
+        .data
+        .iter()
+        .scan(0.0, |total, timing| {
+            *total += timing.total_nanos / 1_000_000.0;
+            Some(*total)
+        })
+        .collect()
+}
+
+/// A line of the total runtime after each day, in milliseconds on a linear scale.
+fn cumulative_chart(timings: &Timings) -> String {
+    let totals = cumulative_millis(timings);
+    let max = totals.last().copied().unwrap_or(0.0).max(f64::EPSILON);
+
+    let plot_width = CHART_WIDTH - MARGIN_LEFT;
+    let plot_height = CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    #[allow(clippy::cast_precision_loss)]
+    let step_width = plot_width / timings.data.len() as f64;
+
+    let point = |i: usize, total: f64| {
+        #[allow(clippy::cast_precision_loss)]
+        let x = MARGIN_LEFT + step_width * (i as f64 + 0.5);
+        let y = MARGIN_TOP + plot_height * (1.0 - total / max);
+        (x, y)
+    };
+
+    let mut svg = svg_open(CHART_WIDTH, CHART_HEIGHT, "Cumulative runtime");
+
+    for fraction in [0.0, 0.25, 0.5, 0.75, 1.0] {
+        let y = MARGIN_TOP + plot_height * (1.0 - fraction);
+        let _ = writeln!(
+            svg,
+            "<line class=\"axis\" x1=\"{MARGIN_LEFT}\" y1=\"{y:.1}\" x2=\"{CHART_WIDTH}\" y2=\"{y:.1}\"/>"
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{:.2}ms</text>",
+            MARGIN_LEFT - 6.0,
+            y + 4.0,
+            max * fraction
+        );
+    }
+
+    let points: Vec<String> = totals
+        .iter()
+        .enumerate()
+        .map(|(i, total)| {
+            let (x, y) = point(i, *total);
+            format!("{x:.1},{y:.1}")
+        })
+        .collect();
+    let _ = writeln!(
+        svg,
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"{PART_1_COLOR}\" stroke-width=\"2\"/>",
+        points.join(" ")
+    );
+
+    for (i, (timing, total)) in timings.data.iter().zip(&totals).enumerate() {
+        let (x, y) = point(i, *total);
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"3\" fill=\"{PART_2_COLOR}\"><title>Day {}: {total:.2}ms</title></circle>",
+            timing.day
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{x:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            CHART_HEIGHT - 8.0,
+            timing.day.into_inner()
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// A small line of the total time of a day over all recorded runs.
+/// Returns [`None`] if there are less than two runs to compare.
+fn sparkline(totals: &[f64]) -> Option<String> {
+    if totals.len() < 2 {
+        return None;
+    }
+
+    let min = totals.iter().copied().fold(f64::MAX, f64::min);
+    let max = totals.iter().copied().fold(f64::MIN, f64::max);
+    let range = (max - min).max(f64::EPSILON);
+    #[allow(clippy::cast_precision_loss)]
+    let step = SPARKLINE_WIDTH / (totals.len() - 1) as f64;
+
+    let points: Vec<String> = totals
+        .iter()
+        .enumerate()
+        .map(|(i, total)| {
+            #[allow(clippy::cast_precision_loss)]
+            let x = step * i as f64;
+            let y = 2.0 + (SPARKLINE_HEIGHT - 4.0) * (1.0 - (total - min) / range);
+            format!("{x:.1},{y:.1}")
+        })
+        .collect();
+
+    Some(format!(
+        "<svg width=\"{SPARKLINE_WIDTH}\" height=\"{SPARKLINE_HEIGHT}\" viewBox=\"0 0 {SPARKLINE_WIDTH} {SPARKLINE_HEIGHT}\" role=\"img\"><title>{} → {} over {} runs</title><polyline points=\"{}\" fill=\"none\" stroke=\"{PART_1_COLOR}\" stroke-width=\"1.5\"/></svg>",
+        format_nanos(totals[0]),
+        format_nanos(totals[totals.len() - 1]),
+        totals.len(),
+        points.join(" ")
+    ))
+}
+
+/// A row per day with its times, heap usage if recorded, and its trend if history is available.
+fn table(timings: &Timings, history: &History, steps: &[Step]) -> String {
+    let has_memory = timings
+        .data
+        .iter()
+        .any(|t| t.part_1_memory.is_some() || t.part_2_memory.is_some());
+    let has_history = !history.data.is_empty();
+
+    let mut html = String::from("<table>\n<tr><th>Day</th>");
+    for step in steps {
+        let _ = write!(html, "<th>{}</th>", capitalize(&step.to_string()));
+    }
+    if has_memory {
+        html.push_str("<th>Part 1 heap</th><th>Part 2 heap</th>");
+    }
+    if has_history {
+        html.push_str("<th>Trend</th>");
+    }
+    html.push_str("</tr>\n");
+
+    for timing in &timings.data {
+        let _ = write!(html, "<tr><td>Day {}</td>", timing.day);
+
+        for step in steps {
+            let _ = write!(html, "<td>{}</td>", step_cell(timing, *step));
+        }
+
+        if has_memory {
+            for memory in [&timing.part_1_memory, &timing.part_2_memory] {
+                let cell = memory
+                    .as_ref()
+                    .map_or_else(|| "-".into(), |memory| escape(&format_usage(memory)));
+                let _ = write!(html, "<td>{cell}</td>");
+            }
+        }
+
+        if has_history {
+            let totals: Vec<f64> = history
+                .for_day(timing.day)
+                .iter()
+                .map(|(_, timing)| timing.total_nanos)
+                .collect();
+            let _ = write!(
+                html,
+                "<td>{}</td>",
+                sparkline(&totals).unwrap_or_else(|| "-".into())
+            );
+        }
+
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</table>");
+    html
+}
+
+fn step_cell(timing: &Timing, step: Step) -> String {
+    match step.nanos(timing) {
+        Some(nanos) => format_nanos(nanos),
+        None => match step {
+            Step::Part(part) if timing.timed_out.contains(&part) => "timeout".into(),
+            Step::Part(part) if timing.failed.contains(&part) => "failed".into(),
+            _ => "-".into(),
+        },
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn svg_open(width: f64, height: f64, title: &str) -> String {
+    format!(
+        "<svg width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" role=\"img\" xmlns=\"http://www.w3.org/2000/svg\">\n<title>{}</title>\n",
+        escape(title)
+    )
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_nanos(nanos: f64) -> String {
+    escape(&format!("{:.1?}", Duration::from_nanos(nanos as u64)))
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+/// Escapes text for use in HTML content and attribute values.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{cumulative_millis, escape, render, sparkline, LogScale};
+    use crate::template::history::{History, HistoryEntry, RunInfo};
+    use crate::template::timings::{Timing, Timings};
+    use crate::template::Day;
+    use crate::year;
+
+    fn timing(day: u8, part_1: &str, part_2: &str, total_nanos: f64) -> Timing {
+        Timing {
+            part_1: Some(part_1.into()),
+            part_2: Some(part_2.into()),
+            total_nanos,
+            ..Timing::new(Day::new(day).unwrap())
+        }
+    }
+
+    fn get_mock_timings() -> Timings {
+        Timings {
+            data: vec![
+                timing(1, "10.0µs", "20.0µs", 30_000.0),
+                timing(2, "1.0ms", "3.0ms", 4_000_000.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn fits_log_scale_to_decades() {
+        let scale = LogScale::fit([10_000.0, 3_000_000.0].into_iter());
+        assert_eq!((scale.min_exp, scale.max_exp), (3, 7));
+        assert_eq!(scale.position(1_000.0), 0.0);
+        assert_eq!(scale.position(10_000.0), 0.25);
+        assert_eq!(scale.position(10_000_000.0), 1.0);
+        assert_eq!(scale.ticks().count(), 5);
+    }
+
+    #[test]
+    fn sums_up_runtime() {
+        assert_eq!(cumulative_millis(&get_mock_timings()), vec![0.03, 4.03]);
+    }
+
+    #[test]
+    fn draws_sparklines_for_several_runs() {
+        assert_eq!(sparkline(&[1000.0]), None);
+        let svg = sparkline(&[2000.0, 1000.0, 1500.0]).unwrap();
+        assert!(svg.contains("<polyline points=\"0.0,2.0 60.0,22.0 120.0,12.0\""));
+        assert!(svg.contains("over 3 runs"));
+    }
+
+    #[test]
+    fn renders_self_contained_page() {
+        let timings = get_mock_timings();
+        let history = History {
+            data: vec![
+                HistoryEntry {
+                    info: RunInfo {
+                        timestamp: 1,
+                        commit: None,
+                        rustc: None,
+                        machine: None,
+                    },
+                    timings: vec![timing(1, "20.0µs", "20.0µs", 40_000.0)],
+                },
+                HistoryEntry {
+                    info: RunInfo {
+                        timestamp: 2,
+                        commit: None,
+                        rustc: None,
+                        machine: None,
+                    },
+                    timings: timings.data.clone(),
+                },
+            ],
+        };
+
+        let html = render(year!(2024), &timings, &history);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("src="));
+        assert_eq!(html.matches("<svg").count(), 3);
+        assert!(html.contains("<th>Trend</th>"));
+        assert!(html.contains("<title>Day 02 part 2: 3.0ms</title>"));
+        assert!(html.contains("Total: <strong>4.03ms</strong> over 2 days."));
+    }
+
+    #[test]
+    fn escapes_text() {
+        assert_eq!(
+            escape("<a href=\"x\">&'"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&#39;"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+/// Selects the input a solution binary runs on, see `cargo solve --input` and `--example`.
+use std::{
+    env,
+    fmt::Display,
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+use crate::template::{data_dir, Day, Year};
+
+/// Flag that runs a solution on a file, or on stdin when passed `-`.
+pub const INPUT_FLAG: &str = "--input";
+
+/// Flag that runs a solution on an example, optionally followed by the number of the example.
+pub const EXAMPLE_FLAG: &str = "--example";
+
+/// Where a solution reads its input from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum InputSource {
+    /// The puzzle input, e.g. `data/2024/inputs/01.txt`.
+    #[default]
+    Puzzle,
+    /// Any file, relative to the working directory.
+    File(PathBuf),
+    Stdin,
+    /// `data/<year>/examples/NN.txt`, or `NN-n.txt` if a number is given.
+    Example(Option<u8>),
+}
+
+impl InputSource {
+    /// Parses the input flags of a solution binary. Other arguments are ignored.
+    pub fn from_args(args: &[String]) -> Result<Self, InputError> {
+        let mut source = Self::Puzzle;
+        let mut iter = args.iter().peekable();
+
+        while let Some(arg) = iter.next() {
+            let next = match arg.as_str() {
+                INPUT_FLAG => match iter.next().map(String::as_str) {
+                    Some("-") => Self::Stdin,
+                    Some(path) if !path.starts_with("--") => Self::File(path.into()),
+                    _ => return Err(InputError::MissingPath),
+                },
+                EXAMPLE_FLAG => {
+                    let number = iter.peek().and_then(|s| s.parse::<u8>().ok());
+                    if number.is_some() {
+                        iter.next();
+                    }
+                    Self::Example(number)
+                }
+                _ => continue,
+            };
+
+            if source != Self::Puzzle {
+                return Err(InputError::Conflicting);
+            }
+            source = next;
+        }
+
+        Ok(source)
+    }
+
+    /// Arguments that select this input when passed to a solution binary.
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            Self::Puzzle => vec![],
+            Self::File(path) => vec![INPUT_FLAG.into(), path.to_string_lossy().into_owned()],
+            Self::Stdin => vec![INPUT_FLAG.into(), "-".into()],
+            Self::Example(None) => vec![EXAMPLE_FLAG.into()],
+            Self::Example(Some(number)) => vec![EXAMPLE_FLAG.into(), number.to_string()],
+        }
+    }
+
+    /// Path of the file this input is read from, if any.
+    pub fn path(&self, year: Year, day: Day) -> Option<PathBuf> {
+        match self {
+            Self::Puzzle => Some(data_dir(year).join("inputs").join(format!("{day}.txt"))),
+            Self::File(path) => Some(path.clone()),
+            Self::Stdin => None,
+            Self::Example(None) => Some(data_dir(year).join("examples").join(format!("{day}.txt"))),
+            Self::Example(Some(number)) => Some(
+                data_dir(year)
+                    .join("examples")
+                    .join(format!("{day}-{number}.txt")),
+            ),
+        }
+    }
+
+    /// Reads the input of a day.
+    pub fn read(&self, year: Year, day: Day) -> io::Result<String> {
+        match self.path(year, day) {
+            Some(path) => fs::read_to_string(env::current_dir()?.join(path)),
+            None => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Puzzle => f.write_str("the puzzle input"),
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Stdin => f.write_str("stdin"),
+            Self::Example(None) => f.write_str("the example"),
+            Self::Example(Some(number)) => write!(f, "example {number}"),
+        }
+    }
+}
+
+/// Reads the input selected by the arguments of the current process.
+/// Used by the `main` function that the `solution!` macro generates.
+///
+/// # Panics
+/// Panics if the arguments are invalid or the input can't be read.
+pub fn read_input(year: Year, day: Day) -> String {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let source = InputSource::from_args(&args).unwrap_or_else(|e| panic!("{e}"));
+    source
+        .read(year, day)
+        .unwrap_or_else(|e| panic!("could not read input from {source}: {e}"))
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug)]
+pub enum InputError {
+    MissingPath,
+    Conflicting,
+}
+
+impl std::error::Error for InputError {}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::MissingPath => {
+                write!(
+                    f,
+                    "`{INPUT_FLAG}` expects a path, or `-` to read from stdin"
+                )
+            }
+            InputError::Conflicting => {
+                write!(f, "pass either `{INPUT_FLAG}` or `{EXAMPLE_FLAG}`, once")
+            }
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{InputError, InputSource};
+    use crate::{day, year};
+
+    fn parse(args: &[&str]) -> Result<InputSource, InputError> {
+        let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+        InputSource::from_args(&args)
+    }
+
+    #[test]
+    fn parses_input_flags() {
+        assert_eq!(parse(&[]).unwrap(), InputSource::Puzzle);
+        assert_eq!(parse(&["--submit", "1"]).unwrap(), InputSource::Puzzle);
+        assert_eq!(
+            parse(&["--input", "other/01.txt"]).unwrap(),
+            InputSource::File(PathBuf::from("other/01.txt"))
+        );
+        assert_eq!(parse(&["--input", "-"]).unwrap(), InputSource::Stdin);
+        assert_eq!(parse(&["--example"]).unwrap(), InputSource::Example(None));
+        assert_eq!(
+            parse(&["--example", "2", "--time"]).unwrap(),
+            InputSource::Example(Some(2))
+        );
+        assert_eq!(
+            parse(&["--example", "--time"]).unwrap(),
+            InputSource::Example(None)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_flags() {
+        assert!(matches!(parse(&["--input"]), Err(InputError::MissingPath)));
+        assert!(matches!(
+            parse(&["--input", "--example"]),
+            Err(InputError::MissingPath)
+        ));
+        assert!(matches!(
+            parse(&["--input", "a.txt", "--example"]),
+            Err(InputError::Conflicting)
+        ));
+    }
+
+    #[test]
+    fn round_trips_args() {
+        for source in [
+            InputSource::Puzzle,
+            InputSource::File(PathBuf::from("a.txt")),
+            InputSource::Stdin,
+            InputSource::Example(None),
+            InputSource::Example(Some(3)),
+        ] {
+            assert_eq!(InputSource::from_args(&source.to_args()).unwrap(), source);
+        }
+    }
+
+    #[test]
+    fn resolves_paths() {
+        let (year, day) = (year!(2024), day!(1));
+        assert_eq!(
+            InputSource::Puzzle.path(year, day),
+            Some(PathBuf::from("data/2024/inputs/01.txt"))
+        );
+        assert_eq!(
+            InputSource::Example(Some(2)).path(year, day),
+            Some(PathBuf::from("data/2024/examples/01-2.txt"))
+        );
+        assert_eq!(InputSource::Stdin.path(year, day), None);
+    }
+}
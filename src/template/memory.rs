@@ -0,0 +1,344 @@
+/// Heap usage of solution parts, captured with the `dhat-heap` feature or the [`CountingAlloc`]
+/// of the `count-allocs` feature.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::HashMap,
+    env,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+use tinyjson::JsonValue;
+
+/// Command-line flag that makes a solution binary profile its heap without writing `dhat-heap.json`.
+pub const MEMORY_FLAG: &str = "--memory";
+
+/// Heap usage of a single run of a part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeapUsage {
+    /// Maximum number of bytes that were allocated at the same time.
+    pub peak_bytes: u64,
+    /// Number of allocations over the whole run.
+    pub total_allocs: u64,
+    /// Number of bytes allocated over the whole run.
+    pub total_bytes: u64,
+}
+
+/// Profiles the heap while a part runs.
+/// Without the `dhat-heap` or `count-allocs` feature, nothing is measured.
+pub struct HeapProfiler {
+    #[cfg(feature = "dhat-heap")]
+    profiler: dhat::Profiler,
+    #[cfg(all(feature = "count-allocs", not(feature = "dhat-heap")))]
+    start: AllocCounts,
+}
+
+impl HeapProfiler {
+    /// Starts profiling. Only one profiler can run at a time.
+    pub fn start() -> Self {
+        Self {
+            // when only the metrics are requested, skip the report dhat writes on drop.
+            #[cfg(feature = "dhat-heap")]
+            profiler: if is_memory_requested() {
+                dhat::Profiler::builder().testing().build()
+            } else {
+                dhat::Profiler::new_heap()
+            },
+            #[cfg(all(feature = "count-allocs", not(feature = "dhat-heap")))]
+            start: AllocCounts::start(),
+        }
+    }
+
+    /// Stops profiling and returns the heap usage since [`HeapProfiler::start`].
+    pub fn finish(self) -> Option<HeapUsage> {
+        #[cfg(feature = "dhat-heap")]
+        {
+            let stats = dhat::HeapStats::get();
+            drop(self.profiler);
+            Some(HeapUsage {
+                peak_bytes: stats.max_bytes as u64,
+                total_allocs: stats.total_blocks,
+                total_bytes: stats.total_bytes,
+            })
+        }
+
+        #[cfg(all(feature = "count-allocs", not(feature = "dhat-heap")))]
+        {
+            self.start.finish()
+        }
+
+        #[cfg(not(any(feature = "dhat-heap", feature = "count-allocs")))]
+        None
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+static ALLOCS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// A global allocator that counts allocations on top of the [`System`] allocator.
+/// Unlike dhat, it needs neither a separate profile nor a rebuild, so it is cheap enough for release runs.
+/// The `solution!` macro installs it when the `count-allocs` feature is enabled.
+///
+/// Counters are shared by all threads of the process, so they include allocations of threads a part spawns.
+pub struct CountingAlloc;
+
+impl CountingAlloc {
+    fn record_alloc(size: usize) {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed);
+        let live = LIVE_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_LIVE_BYTES.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        LIVE_BYTES.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::record_dealloc(layout.size());
+    }
+
+    /// A reallocation counts as a new allocation, the same way dhat counts it.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::record_dealloc(layout.size());
+            Self::record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Counters of the [`CountingAlloc`] when a part started.
+#[cfg_attr(not(feature = "count-allocs"), allow(dead_code))]
+struct AllocCounts {
+    allocs: u64,
+    allocated_bytes: u64,
+    live_bytes: usize,
+}
+
+#[cfg_attr(not(feature = "count-allocs"), allow(dead_code))]
+impl AllocCounts {
+    /// Snapshots the counters and resets the peak, so that it only covers the part.
+    fn start() -> Self {
+        let live_bytes = LIVE_BYTES.load(Ordering::Relaxed);
+        PEAK_LIVE_BYTES.store(live_bytes, Ordering::Relaxed);
+        Self {
+            allocs: ALLOCS.load(Ordering::Relaxed),
+            allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+            live_bytes,
+        }
+    }
+
+    /// Returns the usage since [`AllocCounts::start`].
+    /// Returns [`None`] if the allocator is not installed, i.e. nothing was ever counted.
+    fn finish(self) -> Option<HeapUsage> {
+        let allocs = ALLOCS.load(Ordering::Relaxed);
+        if allocs == 0 {
+            return None;
+        }
+        let peak = PEAK_LIVE_BYTES.load(Ordering::Relaxed);
+        Some(HeapUsage {
+            peak_bytes: peak.saturating_sub(self.live_bytes) as u64,
+            total_allocs: allocs - self.allocs,
+            total_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed) - self.allocated_bytes,
+        })
+    }
+}
+
+/// Whether heap usage was requested from a solution binary.
+pub fn is_memory_requested() -> bool {
+    env::args().any(|x| x == MEMORY_FLAG)
+}
+
+/// Formats a number of bytes with a binary unit, e.g. `1.5 KiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+
+    format!("{value:.1} {unit}")
+}
+
+/// Formats the heap usage of a part as a single line.
+pub fn format_usage(usage: &HeapUsage) -> String {
+    let noun = if usage.total_allocs == 1 {
+        "alloc"
+    } else {
+        "allocs"
+    };
+    format!(
+        "peak {} · {} {noun} · {} total",
+        format_bytes(usage.peak_bytes),
+        usage.total_allocs,
+        format_bytes(usage.total_bytes)
+    )
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&HeapUsage> for JsonValue {
+    fn from(value: &HeapUsage) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        #[allow(clippy::cast_precision_loss)]
+        {
+            map.insert(
+                "peak_bytes".into(),
+                JsonValue::Number(value.peak_bytes as f64),
+            );
+            map.insert(
+                "total_allocs".into(),
+                JsonValue::Number(value.total_allocs as f64),
+            );
+            map.insert(
+                "total_bytes".into(),
+                JsonValue::Number(value.total_bytes as f64),
+            );
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for HeapUsage {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected memory to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected memory.{key} to be a number."))
+        };
+
+        Ok(HeapUsage {
+            peak_bytes: number("peak_bytes")? as u64,
+            total_allocs: number("total_allocs")? as u64,
+            total_bytes: number("total_bytes")? as u64,
+        })
+    }
+}
+
+/// Reads optional heap usage stored under `key` of a JSON object.
+pub fn usage_from_json(
+    json: &HashMap<String, JsonValue>,
+    key: &str,
+) -> Result<Option<HeapUsage>, String> {
+    match json.get(key) {
+        None | Some(JsonValue::Null) => Ok(None),
+        Some(v) => HeapUsage::try_from(v).map(Some),
+    }
+}
+
+/// Converts optional heap usage to JSON, using `null` if absent.
+pub fn usage_to_json(usage: Option<&HeapUsage>) -> JsonValue {
+    usage.map_or(JsonValue::Null, JsonValue::from)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_bytes, format_usage, AllocCounts, CountingAlloc, HeapUsage};
+    use std::alloc::{GlobalAlloc, Layout};
+    use tinyjson::JsonValue;
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0 GiB");
+    }
+
+    #[test]
+    fn formats_usage() {
+        let usage = HeapUsage {
+            peak_bytes: 2048,
+            total_allocs: 14,
+            total_bytes: 4096,
+        };
+        assert_eq!(
+            format_usage(&usage),
+            "peak 2.0 KiB · 14 allocs · 4.0 KiB total"
+        );
+    }
+
+    #[test]
+    fn round_trips_usage() {
+        let usage = HeapUsage {
+            peak_bytes: 232,
+            total_allocs: 3,
+            total_bytes: 276,
+        };
+        let json = JsonValue::from(&usage);
+        assert_eq!(HeapUsage::try_from(&json), Ok(usage));
+    }
+
+    #[test]
+    fn counts_allocations() {
+        let layout = Layout::from_size_align(1024, 8).unwrap();
+
+        let counts = AllocCounts::start();
+        // SAFETY: the layout has a non-zero size and the pointers are freed with the same layout.
+        unsafe {
+            let a = CountingAlloc.alloc(layout);
+            let b = CountingAlloc.alloc(layout);
+            CountingAlloc.dealloc(a, layout);
+            CountingAlloc.dealloc(b, layout);
+            let c = CountingAlloc.alloc(layout);
+            CountingAlloc.dealloc(c, layout);
+        }
+        let usage = counts.finish().unwrap();
+
+        assert_eq!(
+            usage,
+            HeapUsage {
+                peak_bytes: 2 * 1024,
+                total_allocs: 3,
+                total_bytes: 3 * 1024,
+            }
+        );
+    }
+}
@@ -1,43 +1,71 @@
+use std::path::{Path, PathBuf};
 use std::{env, fs};
 
-pub mod aoc_cli;
+pub mod aoc_client;
+pub mod bench;
 pub mod commands;
+pub mod example_tests;
+pub mod input;
+pub mod memory;
+pub mod protocol;
+pub mod registry;
+pub mod regressions;
+pub mod run_multi;
 pub mod runner;
 
+pub use calendar::Calendar;
 pub use day::*;
+pub use solution::Solution;
+pub use year::*;
 
+mod answers;
+pub mod calendar;
 mod day;
+mod history;
+mod html_markdown;
+mod html_report;
+mod puzzle_examples;
 mod readme_benchmarks;
-mod run_multi;
+mod solution;
+mod submissions;
 mod timings;
+mod year;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
-/// Helper function that reads a text file to a string.
+/// Helper function that reads a text file of a year to a string. E.g. like `data/2024/inputs/01.txt`.
 #[must_use]
-pub fn read_file(folder: &str, day: Day) -> String {
+pub fn read_file(folder: &str, year: Year, day: Day) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = data_dir(year).join(folder).join(format!("{day}.txt"));
+    let filepath = cwd.join(filepath);
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
-pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
+pub fn read_file_part(folder: &str, year: Year, day: Day, part: u8) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
+    let filepath = data_dir(year)
         .join(folder)
         .join(format!("{day}-{part}.txt"));
+    let filepath = cwd.join(filepath);
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
-/// Creates the constant `DAY` and sets up the input and runner for each part.
+/// Directory holding the inputs, puzzles and stored data of a year, relative to the crate root.
+#[must_use]
+pub fn data_dir(year: Year) -> PathBuf {
+    Path::new("data").join(year.to_string())
+}
+
+/// Creates the constants `YEAR` and `DAY` and sets up the input and runner for each part.
 ///
+/// The year is read from the name of the solution file, e.g. `src/bin/2024_01.rs`.
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
 #[macro_export]
 macro_rules! solution {
@@ -52,17 +80,71 @@ macro_rules! solution {
     };
 
     (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+        /// The year of this solution.
+        const YEAR: $crate::template::Year = $crate::template::Year::from_bin_path(file!());
+
         /// The current day.
-        const DAY: $crate::template::Day = $crate::day!($day);
+        const DAY: $crate::template::Day = $crate::day!(YEAR, $day);
 
         #[cfg(feature = "dhat-heap")]
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
+        // registered solutions share the allocator of the runner, see `src/main.rs`.
+        #[cfg(all(feature = "count-allocs", not(feature = "dhat-heap"), not(feature = "registry")))]
+        #[global_allocator]
+        static ALLOC: $crate::template::memory::CountingAlloc = $crate::template::memory::CountingAlloc;
+
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            let input = $crate::template::input::read_input(YEAR, DAY);
+            exit_on_failure(&[$( run_part($func, &input, YEAR, DAY, $part) ),*]);
+        }
+
+        /// Entry point for the in-process registry that backs `cargo all` and `cargo time`.
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        pub fn __run_registered(
+            input: &str,
+            bench: Option<&$crate::template::bench::BenchConfig>,
+            on_report: &mut dyn FnMut($crate::template::protocol::Report),
+        ) {
+            use $crate::template::runner::*;
+            $( on_report($crate::template::protocol::Report::Part(report_part($func, input, $part, bench))); )*
+        }
+    };
+
+    ($day:expr, $solution:ty) => {
+        /// The year of this solution.
+        const YEAR: $crate::template::Year = $crate::template::Year::from_bin_path(file!());
+
+        /// The current day.
+        const DAY: $crate::template::Day = $crate::day!(YEAR, $day);
+
+        #[cfg(feature = "dhat-heap")]
+        #[global_allocator]
+        static ALLOC: dhat::Alloc = dhat::Alloc;
+
+        // registered solutions share the allocator of the runner, see `src/main.rs`.
+        #[cfg(all(feature = "count-allocs", not(feature = "dhat-heap"), not(feature = "registry")))]
+        #[global_allocator]
+        static ALLOC: $crate::template::memory::CountingAlloc = $crate::template::memory::CountingAlloc;
+
+        fn main() {
+            use $crate::template::runner::*;
+            let input = $crate::template::input::read_input(YEAR, DAY);
+            exit_on_failure(&run_parsed::<$solution>(&input, YEAR, DAY));
+        }
+
+        /// Entry point for the in-process registry that backs `cargo all` and `cargo time`.
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        pub fn __run_registered(
+            input: &str,
+            bench: Option<&$crate::template::bench::BenchConfig>,
+            on_report: &mut dyn FnMut($crate::template::protocol::Report),
+        ) {
+            $crate::template::runner::report_parsed::<$solution>(input, bench, on_report);
         }
     };
 }
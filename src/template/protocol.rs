@@ -0,0 +1,502 @@
+/// Machine-readable output of solution binaries.
+/// When enabled, every part is reported as a single line of JSON on stdout.
+use std::{collections::HashMap, env, str::FromStr, time::Duration};
+use tinyjson::JsonValue;
+
+use crate::template::bench::{stats_from_json, stats_to_json, BenchStats};
+use crate::template::memory::{usage_from_json, usage_to_json, HeapUsage};
+use crate::template::runner::Outcome;
+
+/// Command-line flag that enables JSON output in a solution binary.
+pub const JSON_FLAG: &str = "--json";
+
+/// Environment variable that enables JSON output when set to `json`.
+pub const OUTPUT_ENV: &str = "AOC_OUTPUT";
+
+/// Returns whether the current process should report results as JSON lines.
+pub fn is_json_output() -> bool {
+    env::args().any(|x| x == JSON_FLAG) || env::var(OUTPUT_ENV).is_ok_and(|v| v == "json")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartStatus {
+    Solved,
+    Unsolved,
+    /// The part was cancelled by the runner after exceeding a timeout.
+    TimedOut,
+    /// The part returned an error.
+    Failed,
+}
+
+impl PartStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            PartStatus::Solved => "solved",
+            PartStatus::Unsolved => "unsolved",
+            PartStatus::TimedOut => "timed_out",
+            PartStatus::Failed => "failed",
+        }
+    }
+}
+
+impl FromStr for PartStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "solved" => Ok(PartStatus::Solved),
+            "unsolved" => Ok(PartStatus::Unsolved),
+            "timed_out" => Ok(PartStatus::TimedOut),
+            "failed" => Ok(PartStatus::Failed),
+            _ => Err(format!("unknown part status `{s}`.")),
+        }
+    }
+}
+
+/// The result of running a single part.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartReport {
+    pub part: u8,
+    pub answer: Option<String>,
+    pub nanos: f64,
+    pub samples: u128,
+    pub status: PartStatus,
+    /// Benchmark statistics, present for timed runs.
+    pub stats: Option<BenchStats>,
+    /// Heap usage, present for solutions built with the `dhat-heap` feature.
+    pub memory: Option<HeapUsage>,
+    /// The error of a failed part followed by its sources, outermost first.
+    pub error: Vec<String>,
+}
+
+impl PartReport {
+    pub fn duration(&self) -> Duration {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Duration::from_nanos(self.nanos as u64)
+    }
+
+    pub fn outcome(&self) -> Outcome<&str> {
+        match (&self.answer, self.status) {
+            (_, PartStatus::Failed) => Outcome::Failed(self.error.clone()),
+            (Some(answer), _) => Outcome::Solved(answer),
+            (None, _) => Outcome::Unsolved,
+        }
+    }
+
+    /// Serializes the report to a single line of JSON.
+    pub fn to_json_line(&self) -> String {
+        JsonValue::from(self)
+            .stringify()
+            .expect("part report is serializable")
+    }
+
+    /// Parses a line of output. Returns `None` for lines that are not part reports.
+    pub fn from_json_line(line: &str) -> Option<Self> {
+        match Report::from_json_line(line)? {
+            Report::Part(report) => Some(report),
+            Report::Parse(_) => None,
+        }
+    }
+}
+
+/// The time spent parsing the input of a [`Solution`](crate::template::Solution).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseReport {
+    pub nanos: f64,
+    pub samples: u128,
+    /// Benchmark statistics, present for timed runs.
+    pub stats: Option<BenchStats>,
+}
+
+impl ParseReport {
+    pub fn duration(&self) -> Duration {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Duration::from_nanos(self.nanos as u64)
+    }
+
+    /// Serializes the report to a single line of JSON.
+    pub fn to_json_line(&self) -> String {
+        JsonValue::from(self)
+            .stringify()
+            .expect("parse report is serializable")
+    }
+}
+
+/// Any line of machine-readable output.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Report {
+    Parse(ParseReport),
+    Part(PartReport),
+}
+
+impl Report {
+    /// Parses a line of output. Returns `None` for lines that are not reports,
+    /// e.g. debug output of a solution.
+    pub fn from_json_line(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if !line.starts_with('{') {
+            return None;
+        }
+        let json = JsonValue::from_str(line).ok()?;
+        let is_parse_step = json
+            .get::<HashMap<String, JsonValue>>()?
+            .get("step")
+            .and_then(|v| v.get::<String>())
+            .is_some_and(|step| step == "parse");
+
+        if is_parse_step {
+            ParseReport::try_from(&json).ok().map(Report::Parse)
+        } else {
+            PartReport::try_from(&json).ok().map(Report::Part)
+        }
+    }
+}
+
+/// Progress of a step, reported ahead of its result so that runners can time out single executions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Progress {
+    /// A step started its first execution.
+    Started,
+    /// The first execution of a step finished and it is run `runs` more times to bench it.
+    Benching { runs: u32 },
+}
+
+impl Progress {
+    /// Serializes the progress to a single line of JSON.
+    pub fn to_json_line(self) -> String {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        match self {
+            Progress::Started => {
+                map.insert("progress".into(), JsonValue::String("started".into()));
+            }
+            Progress::Benching { runs } => {
+                map.insert("progress".into(), JsonValue::String("benching".into()));
+                map.insert("runs".into(), JsonValue::Number(runs.into()));
+            }
+        }
+        JsonValue::Object(map)
+            .stringify()
+            .expect("progress is serializable")
+    }
+
+    /// Parses a line of output. Returns `None` for lines that are not progress.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn from_json_line(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if !line.starts_with('{') {
+            return None;
+        }
+        let json = JsonValue::from_str(line).ok()?;
+        let json = json.get::<HashMap<String, JsonValue>>()?;
+
+        match json.get("progress")?.get::<String>()?.as_str() {
+            "started" => Some(Progress::Started),
+            "benching" => {
+                let runs = json.get("runs")?.get::<f64>()?;
+                Some(Progress::Benching { runs: *runs as u32 })
+            }
+            _ => None,
+        }
+    }
+
+    /// Prints the progress if the current process reports results as JSON lines.
+    pub fn report(self) {
+        if is_json_output() {
+            println!("{}", self.to_json_line());
+        }
+    }
+}
+
+/// All reports printed by a single run of a solution binary.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SolutionReport {
+    /// Present for solutions implementing [`Solution`](crate::template::Solution).
+    pub parse: Option<ParseReport>,
+    pub parts: Vec<PartReport>,
+    /// Why the solution stopped before reporting all parts, e.g. a panic while parsing.
+    pub crashed: Option<String>,
+}
+
+impl SolutionReport {
+    pub fn push(&mut self, report: Report) {
+        match report {
+            Report::Parse(report) => self.parse = Some(report),
+            Report::Part(report) => self.parts.push(report),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parse.is_none() && self.parts.is_empty()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&ParseReport> for JsonValue {
+    fn from(value: &ParseReport) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("step".into(), JsonValue::String("parse".into()));
+        map.insert("nanos".into(), JsonValue::Number(value.nanos));
+        #[allow(clippy::cast_precision_loss)]
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
+        map.insert("stats".into(), stats_to_json(value.stats.as_ref()));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for ParseReport {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected report to be a JSON object.")?;
+
+        let nanos = json
+            .get("nanos")
+            .and_then(|v| v.get::<f64>().copied())
+            .ok_or("Expected report.nanos to be a number.")?;
+
+        let samples = json
+            .get("samples")
+            .and_then(|v| v.get::<f64>())
+            .map(|v| *v as u128)
+            .ok_or("Expected report.samples to be a number.")?;
+
+        let stats = stats_from_json(json, "stats")?;
+
+        Ok(ParseReport {
+            nanos,
+            samples,
+            stats,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&PartReport> for JsonValue {
+    fn from(value: &PartReport) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert(
+            "answer".into(),
+            value
+                .answer
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+        map.insert("nanos".into(), JsonValue::Number(value.nanos));
+        #[allow(clippy::cast_precision_loss)]
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
+        map.insert(
+            "status".into(),
+            JsonValue::String(value.status.as_str().into()),
+        );
+        map.insert("stats".into(), stats_to_json(value.stats.as_ref()));
+        map.insert("memory".into(), usage_to_json(value.memory.as_ref()));
+
+        if !value.error.is_empty() {
+            map.insert(
+                "error".into(),
+                JsonValue::Array(
+                    value
+                        .error
+                        .iter()
+                        .map(|message| JsonValue::String(message.clone()))
+                        .collect(),
+                ),
+            );
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for PartReport {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected report to be a JSON object.")?;
+
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .map(|v| *v as u8)
+            .ok_or("Expected report.part to be a number.")?;
+
+        let answer = json
+            .get("answer")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected report.answer to be null or string.")?;
+
+        let nanos = json
+            .get("nanos")
+            .and_then(|v| v.get::<f64>().copied())
+            .ok_or("Expected report.nanos to be a number.")?;
+
+        let samples = json
+            .get("samples")
+            .and_then(|v| v.get::<f64>())
+            .map(|v| *v as u128)
+            .ok_or("Expected report.samples to be a number.")?;
+
+        let status = json
+            .get("status")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected report.status to be a string.")?
+            .parse()?;
+
+        let stats = stats_from_json(json, "stats")?;
+        let memory = usage_from_json(json, "memory")?;
+
+        let error = match json.get("error") {
+            None | Some(JsonValue::Null) => vec![],
+            Some(JsonValue::Array(messages)) => messages
+                .iter()
+                .map(|message| match message {
+                    JsonValue::String(message) => Ok(message.clone()),
+                    _ => Err("Expected report.error to contain strings."),
+                })
+                .collect::<Result<_, _>>()?,
+            Some(_) => return Err("Expected report.error to be an array.".into()),
+        };
+
+        Ok(PartReport {
+            part,
+            answer: answer.cloned(),
+            nanos,
+            samples,
+            status,
+            stats,
+            memory,
+            error,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{ParseReport, PartReport, PartStatus, Progress, Report};
+    use crate::template::bench::BenchStats;
+    use crate::template::memory::HeapUsage;
+
+    #[test]
+    fn round_trips_reports() {
+        let report = PartReport {
+            part: 2,
+            answer: Some("line 1\nline \"2\"".into()),
+            nanos: 74130.0,
+            samples: 100,
+            status: PartStatus::Solved,
+            stats: None,
+            memory: None,
+            error: vec![],
+        };
+
+        let line = report.to_json_line();
+        assert!(!line.contains('\n'));
+        assert_eq!(PartReport::from_json_line(&line), Some(report));
+    }
+
+    #[test]
+    fn round_trips_stats() {
+        let report = PartReport {
+            part: 1,
+            answer: Some("42".into()),
+            nanos: 11.5,
+            samples: 10,
+            status: PartStatus::Solved,
+            stats: Some(BenchStats {
+                samples: 10,
+                min: 10.0,
+                median: 11.5,
+                mean: 20.2,
+                p95: 59.5,
+                max: 100.0,
+                std_dev: 28.05,
+                outliers: 1,
+            }),
+            memory: Some(HeapUsage {
+                peak_bytes: 232,
+                total_allocs: 3,
+                total_bytes: 276,
+            }),
+            error: vec![],
+        };
+
+        let line = report.to_json_line();
+        assert_eq!(PartReport::from_json_line(&line), Some(report));
+    }
+
+    #[test]
+    fn handles_unsolved_parts() {
+        let line = r#"{"part":1,"answer":null,"nanos":10,"samples":1,"status":"unsolved"}"#;
+        let report = PartReport::from_json_line(line).unwrap();
+        assert_eq!(report.answer, None);
+        assert_eq!(report.status, PartStatus::Unsolved);
+    }
+
+    #[test]
+    fn round_trips_failed_parts() {
+        let report = PartReport {
+            part: 1,
+            answer: None,
+            nanos: 120.0,
+            samples: 1,
+            status: PartStatus::Failed,
+            stats: None,
+            memory: None,
+            error: vec![
+                "invalid rule on line 3".into(),
+                "invalid digit found in string".into(),
+            ],
+        };
+
+        let line = report.to_json_line();
+        assert_eq!(PartReport::from_json_line(&line), Some(report));
+    }
+
+    #[test]
+    fn distinguishes_parse_reports() {
+        let report = ParseReport {
+            nanos: 1200.0,
+            samples: 1,
+            stats: None,
+        };
+
+        let line = report.to_json_line();
+        assert_eq!(PartReport::from_json_line(&line), None);
+        assert_eq!(Report::from_json_line(&line), Some(Report::Parse(report)));
+    }
+
+    #[test]
+    fn ignores_other_output() {
+        assert_eq!(
+            PartReport::from_json_line("Part 1: 42 (1.0ms @ 5 samples)"),
+            None
+        );
+        assert_eq!(PartReport::from_json_line("{ \"debug\": true }"), None);
+        assert_eq!(PartReport::from_json_line("{ not json"), None);
+        assert_eq!(PartReport::from_json_line("(1.0ms @ 10 samples)"), None);
+    }
+
+    #[test]
+    fn round_trips_progress() {
+        for progress in [Progress::Started, Progress::Benching { runs: 13 }] {
+            let line = progress.to_json_line();
+            assert_eq!(Progress::from_json_line(&line), Some(progress));
+            assert_eq!(Report::from_json_line(&line), None);
+        }
+        assert_eq!(Progress::from_json_line("{\"part\": 1}"), None);
+    }
+}
@@ -0,0 +1,264 @@
+/// Fence that opens and closes a code block.
+const FENCE: &str = "```";
+
+/// Introduces an example input, rather than a drawing of an intermediate step.
+const EXAMPLE_HINT: &str = "example";
+
+/// Introduce an example in part two that differs from the one of part one.
+const NEW_EXAMPLE_HINTS: [&str; 5] = [
+    "new example",
+    "different example",
+    "another example",
+    "second example",
+    "larger example",
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CodeBlock {
+    pub content: String,
+    /// The paragraph before the block, which usually tells what the block shows.
+    pub context: String,
+}
+
+impl CodeBlock {
+    fn mentions(&self, hint: &str) -> bool {
+        self.context.to_lowercase().contains(hint)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PuzzlePart {
+    pub blocks: Vec<CodeBlock>,
+    /// Highlighted values of the text, in order of appearance.
+    pub highlights: Vec<String>,
+}
+
+/// Which code block of a part holds its example input.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BlockGuess {
+    None,
+    Certain(usize),
+    /// Several blocks qualify. `likely` is the best guess, if there is one.
+    Ambiguous {
+        candidates: Vec<usize>,
+        likely: Option<usize>,
+    },
+}
+
+/// The example inputs and highlighted values of a puzzle description stored by `cargo download`.
+/// Descriptions are converted by `html_markdown`: examples are fenced code blocks and
+/// highlighted values such as the answers are rendered as *`11`*.
+#[derive(Debug)]
+pub struct Puzzle {
+    pub parts: Vec<PuzzlePart>,
+}
+
+impl Puzzle {
+    pub fn parse(markdown: &str) -> Self {
+        let mut parts = vec![PuzzlePart::default()];
+        let mut block: Option<String> = None;
+        let mut context = String::new();
+
+        for line in markdown.lines() {
+            let part = parts.last_mut().unwrap();
+
+            if let Some(content) = &mut block {
+                if line == FENCE {
+                    part.blocks.push(CodeBlock {
+                        content: block.take().unwrap(),
+                        context: context.clone(),
+                    });
+                } else {
+                    content.push_str(line);
+                    content.push('\n');
+                }
+            } else if line == FENCE {
+                block = Some(String::new());
+            } else if line.starts_with("## ") && line.contains("Part Two") {
+                parts.push(PuzzlePart::default());
+                context.clear();
+            } else if !line.trim().is_empty() {
+                part.highlights.extend(highlights(line));
+                context = line.to_string();
+            }
+        }
+
+        Self { parts }
+    }
+
+    pub fn part(&self, part: u8) -> Option<&PuzzlePart> {
+        self.parts.get(usize::from(part).checked_sub(1)?)
+    }
+
+    /// The last highlighted value of a part, which usually is the answer for its example.
+    pub fn answer(&self, part: u8) -> Option<&str> {
+        self.part(part)?.highlights.last().map(String::as_str)
+    }
+
+    /// Guesses which block of part one is the example input.
+    pub fn guess_example(&self) -> BlockGuess {
+        let Some(part) = self.part(1) else {
+            return BlockGuess::None;
+        };
+
+        let candidates: Vec<usize> = (0..part.blocks.len()).collect();
+        let hinted: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&i| part.blocks[i].mentions(EXAMPLE_HINT))
+            .collect();
+
+        match (candidates.as_slice(), hinted.as_slice()) {
+            ([], _) => BlockGuess::None,
+            ([only], _) | (_, [only]) => BlockGuess::Certain(*only),
+            _ => BlockGuess::Ambiguous {
+                likely: hinted.first().or(candidates.first()).copied(),
+                candidates,
+            },
+        }
+    }
+
+    /// Guesses which block of part two is an example that part one does not use.
+    /// Most blocks of part two only draw the example of part one, so a block is only
+    /// certain when the text announces a new example.
+    pub fn guess_part_two_example(&self, example: Option<&str>) -> BlockGuess {
+        let Some(part) = self.part(2) else {
+            return BlockGuess::None;
+        };
+
+        let candidates: Vec<usize> = (0..part.blocks.len())
+            .filter(|&i| Some(part.blocks[i].content.trim()) != example.map(str::trim))
+            .collect();
+        let hinted: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&i| NEW_EXAMPLE_HINTS.iter().any(|h| part.blocks[i].mentions(h)))
+            .collect();
+
+        match (candidates.as_slice(), hinted.as_slice()) {
+            ([], _) => BlockGuess::None,
+            (_, [only]) => BlockGuess::Certain(*only),
+            _ => BlockGuess::Ambiguous {
+                likely: hinted.first().copied().or_else(|| {
+                    candidates
+                        .iter()
+                        .copied()
+                        .find(|&i| part.blocks[i].mentions(EXAMPLE_HINT))
+                }),
+                candidates,
+            },
+        }
+    }
+}
+
+/// Values highlighted as *`value`* in a line of text.
+fn highlights(line: &str) -> Vec<String> {
+    let mut found = vec![];
+    let mut rest = line;
+
+    while let Some(start) = rest.find("*`") {
+        let value = &rest[start + 2..];
+        let Some(end) = value.find("`*") else {
+            break;
+        };
+        found.push(value[..end].to_string());
+        rest = &value[end + 2..];
+    }
+
+    found
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{highlights, BlockGuess, Puzzle};
+
+    const PUZZLE: &str = "## --- Day 1: Test ---
+
+Some introduction with a *`highlight`*.
+
+For example:
+
+```
+3   4
+4   3
+```
+
+The distances are *`1`* and *`1`*, so the total is *`2`*.
+
+## --- Part Two ---
+
+This time, the result of the example is:
+
+```
+12
+```
+
+The answer is *`31`*.";
+
+    #[test]
+    fn finds_blocks_and_answers() {
+        let puzzle = Puzzle::parse(PUZZLE);
+
+        assert_eq!(puzzle.parts.len(), 2);
+        assert_eq!(puzzle.parts[0].blocks[0].content, "3   4\n4   3\n");
+        assert_eq!(puzzle.parts[0].blocks[0].context, "For example:");
+        assert_eq!(puzzle.answer(1), Some("2"));
+        assert_eq!(puzzle.answer(2), Some("31"));
+        assert_eq!(puzzle.answer(3), None);
+    }
+
+    #[test]
+    fn guesses_example_blocks() {
+        let puzzle = Puzzle::parse(PUZZLE);
+        assert_eq!(puzzle.guess_example(), BlockGuess::Certain(0));
+
+        // the block of part two only shows a result, so it needs confirmation.
+        assert_eq!(
+            puzzle.guess_part_two_example(Some("3   4\n4   3\n")),
+            BlockGuess::Ambiguous {
+                candidates: vec![0],
+                likely: Some(0)
+            }
+        );
+
+        let puzzle = Puzzle::parse(&PUZZLE.replace("the result of", "a new example"));
+        assert_eq!(
+            puzzle.guess_part_two_example(Some("3   4\n4   3\n")),
+            BlockGuess::Certain(0)
+        );
+
+        let puzzle = Puzzle::parse(&PUZZLE.replace("12", "3   4\n4   3"));
+        assert_eq!(
+            puzzle.guess_part_two_example(Some("3   4\n4   3\n")),
+            BlockGuess::None
+        );
+    }
+
+    #[test]
+    fn prefers_blocks_introduced_as_examples() {
+        let puzzle = Puzzle::parse(
+            "A map:\n\n```\n#.#\n```\n\nAn example:\n\n```\n1\n```\n\nA drawing:\n\n```\n2\n```",
+        );
+        assert_eq!(puzzle.guess_example(), BlockGuess::Certain(1));
+
+        let puzzle = Puzzle::parse("A map:\n\n```\n#.#\n```\n\nA drawing:\n\n```\n2\n```");
+        assert_eq!(
+            puzzle.guess_example(),
+            BlockGuess::Ambiguous {
+                candidates: vec![0, 1],
+                likely: Some(0)
+            }
+        );
+    }
+
+    #[test]
+    fn finds_highlights() {
+        assert_eq!(
+            highlights("*`a`* and `b` and *c* and *`-4`*."),
+            vec!["a", "-4"]
+        );
+        assert!(highlights("unterminated *`a").is_empty());
+    }
+}
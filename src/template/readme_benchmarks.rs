@@ -2,8 +2,9 @@
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
 use std::{fs, io};
 
+use crate::template::memory::{format_bytes, HeapUsage};
 use crate::template::timings::Timings;
-use crate::template::Day;
+use crate::template::{Day, Year};
 
 static MARKER: &str = "<!--- benchmarking table --->";
 
@@ -26,8 +27,8 @@ pub struct TablePosition {
 }
 
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn get_path_for_bin(year: Year, day: Day) -> String {
+    format!("./src/bin/{year}_{day}.rs")
 }
 
 fn locate_table(readme: &str) -> Result<TablePosition, Error> {
@@ -52,47 +53,142 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
+fn construct_table(prefix: &str, years: Vec<(Year, Timings)>) -> String {
     let header = format!("{prefix} Benchmarks");
 
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
-    ];
-
-    for timing in timings.data {
-        let path = get_path_for_bin(timing.day);
-        lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
-            timing.day.into_inner(),
-            path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
-        ));
+    let mut lines: Vec<String> = vec![MARKER.into(), header];
+
+    for (year, timings) in years {
+        let total_millis = timings.total_millis();
+
+        // only show a parse column once a solution reports its parse time separately.
+        let has_parse = timings.data.iter().any(|t| t.parse.is_some());
+        // heap usage is only recorded by `cargo time --memory`.
+        let has_memory = timings
+            .data
+            .iter()
+            .any(|t| t.part_1_memory.is_some() || t.part_2_memory.is_some());
+
+        lines.push(String::new());
+        lines.push(format!("{prefix}# {year}"));
+        lines.push(String::new());
+
+        let (mut header, mut alignment) = if has_parse {
+            (
+                String::from("| Day | Parse | Part 1 | Part 2 |"),
+                String::from("| :---: | :---: | :---: | :---:  |"),
+            )
+        } else {
+            (
+                String::from("| Day | Part 1 | Part 2 |"),
+                String::from("| :---: | :---: | :---:  |"),
+            )
+        };
+        if has_memory {
+            header.push_str(" Part 1 heap | Part 2 heap |");
+            alignment.push_str(" :---: | :---: |");
+        }
+        lines.push(header);
+        lines.push(alignment);
+
+        for timing in timings.data {
+            let path = get_path_for_bin(year, timing.day);
+            let parse = if has_parse {
+                format!(" `{}` |", timing.parse.unwrap_or_else(|| "-".into()))
+            } else {
+                String::new()
+            };
+            // parts that were cancelled or failed are marked, so that they don't look unsolved.
+            let part = |time: Option<String>, part: u8| {
+                time.unwrap_or_else(|| {
+                    if timing.timed_out.contains(&part) {
+                        "timeout".into()
+                    } else if timing.failed.contains(&part) {
+                        "failed".into()
+                    } else {
+                        "-".into()
+                    }
+                })
+            };
+            let memory = if has_memory {
+                format!(
+                    " {} | {} |",
+                    format_memory(timing.part_1_memory.as_ref()),
+                    format_memory(timing.part_2_memory.as_ref())
+                )
+            } else {
+                String::new()
+            };
+            lines.push(format!(
+                "| [Day {}]({}) |{} `{}` | `{}` |{}",
+                timing.day.into_inner(),
+                path,
+                parse,
+                part(timing.part_1.clone(), 1),
+                part(timing.part_2.clone(), 2),
+                memory
+            ));
+        }
+
+        lines.push(String::new());
+        lines.push(format!("**Total: {total_millis:.2}ms**"));
     }
 
-    lines.push(String::new());
-    lines.push(format!("**Total: {total_millis:.2}ms**"));
     lines.push(MARKER.into());
 
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
+/// Formats the heap usage of a part as a table cell: peak bytes, allocations and total bytes allocated.
+fn format_memory(memory: Option<&HeapUsage>) -> String {
+    memory.map_or_else(
+        || "-".into(),
+        |memory| {
+            format!(
+                "`{}` · {} allocs · `{}`",
+                format_bytes(memory.peak_bytes),
+                memory.total_allocs,
+                format_bytes(memory.total_bytes)
+            )
+        },
+    )
+}
+
+fn update_content(s: &mut String, years: Vec<(Year, Timings)>) -> Result<(), Error> {
     let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+    let table = construct_table("##", years);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
 
-pub fn update(timings: Timings) -> Result<(), Error> {
+/// Years with stored timings, newest first.
+fn stored_years() -> Vec<(Year, Timings)> {
+    let Ok(entries) = fs::read_dir("data") else {
+        return vec![];
+    };
+
+    let mut years: Vec<(Year, Timings)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<Year>().ok())
+        .map(|year| (year, Timings::read_from_file(year)))
+        .filter(|(_, timings)| !timings.data.is_empty())
+        .collect();
+
+    years.sort_unstable_by_key(|(year, _)| std::cmp::Reverse(*year));
+    years
+}
+
+/// Updates the README with the timings of `year` and the stored timings of all other years.
+pub fn update(year: Year, timings: Timings) -> Result<(), Error> {
     let path = "README.md";
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
-    let total_millis = timings.total_millis();
-    update_content(&mut readme, timings, total_millis)?;
+
+    let mut years = stored_years();
+    years.retain(|(y, _)| *y != year);
+    years.push((year, timings));
+    years.sort_unstable_by_key(|(year, _)| std::cmp::Reverse(*year));
+
+    update_content(&mut readme, years)?;
     fs::write(path, &readme)?;
     Ok(())
 }
@@ -100,28 +196,35 @@ pub fn update(timings: Timings) -> Result<(), Error> {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use crate::template::memory::HeapUsage;
+    use crate::template::timings::{Timing, Timings};
+    use crate::template::Year;
+    use crate::{day, year};
+
+    fn get_mock_years() -> Vec<(Year, Timings)> {
+        vec![(year!(2024), get_mock_timings())]
+    }
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
-                    day: day!(1),
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    ..Timing::new(day!(1))
                 },
                 Timing {
-                    day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    ..Timing::new(day!(2))
                 },
                 Timing {
-                    day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: Some("50ms".into()),
                     total_nanos: 9e+10,
+                    ..Timing::new(day!(4))
                 },
             ],
         }
@@ -131,28 +234,28 @@ mod tests {
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_years()).unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_years()).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_years()).unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_years()).unwrap();
+        update_content(&mut s, get_mock_years()).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -160,24 +263,103 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_years()).unwrap();
         let expected = [
             "foo",
             "bar",
             "<!--- benchmarking table --->",
             "## Benchmarks",
             "",
+            "### 2024",
+            "",
             "| Day | Part 1 | Part 2 |",
             "| :---: | :---: | :---:  |",
-            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
-            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
-            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "| [Day 1](./src/bin/2024_01.rs) | `10ms` | `20ms` |",
+            "| [Day 2](./src/bin/2024_02.rs) | `30ms` | `40ms` |",
+            "| [Day 4](./src/bin/2024_04.rs) | `40ms` | `50ms` |",
             "",
-            "**Total: 190.00ms**",
+            "**Total: 190000.00ms**",
             "<!--- benchmarking table --->",
             "baz",
         ]
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_benchmarks_with_parse_times() {
+        let mut timings = get_mock_timings();
+        timings.data[1].parse = Some("5ms".into());
+
+        let mut s = format!("{}{}", MARKER, MARKER);
+        update_content(&mut s, vec![(year!(2024), timings)]).unwrap();
+
+        assert!(s.contains("| Day | Parse | Part 1 | Part 2 |"));
+        assert!(s.contains("| [Day 1](./src/bin/2024_01.rs) | `-` | `10ms` | `20ms` |"));
+        assert!(s.contains("| [Day 2](./src/bin/2024_02.rs) | `5ms` | `30ms` | `40ms` |"));
+    }
+
+    #[test]
+    fn format_timed_out_parts() {
+        let mut timings = get_mock_timings();
+        timings.data[2].part_2 = None;
+        timings.data[2].timed_out = vec![2];
+
+        let mut s = format!("{}{}", MARKER, MARKER);
+        update_content(&mut s, vec![(year!(2024), timings)]).unwrap();
+
+        assert!(s.contains("| [Day 4](./src/bin/2024_04.rs) | `40ms` | `timeout` |"));
+    }
+
+    #[test]
+    fn format_failed_parts() {
+        let mut timings = get_mock_timings();
+        timings.data[2].part_1 = None;
+        timings.data[2].failed = vec![1];
+
+        let mut s = format!("{}{}", MARKER, MARKER);
+        update_content(&mut s, vec![(year!(2024), timings)]).unwrap();
+
+        assert!(s.contains("| [Day 4](./src/bin/2024_04.rs) | `failed` | `50ms` |"));
+    }
+
+    #[test]
+    fn format_heap_usage() {
+        let mut timings = get_mock_timings();
+        timings.data[0].part_1_memory = Some(HeapUsage {
+            peak_bytes: 2048,
+            total_allocs: 14,
+            total_bytes: 3584,
+        });
+
+        let mut s = format!("{}{}", MARKER, MARKER);
+        update_content(&mut s, vec![(year!(2024), timings)]).unwrap();
+
+        assert!(s.contains("| Day | Part 1 | Part 2 | Part 1 heap | Part 2 heap |"));
+        assert!(s.contains(
+            "| [Day 1](./src/bin/2024_01.rs) | `10ms` | `20ms` | `2.0 KiB` · 14 allocs · `3.5 KiB` | - |"
+        ));
+        assert!(s.contains("| [Day 2](./src/bin/2024_02.rs) | `30ms` | `40ms` | - | - |"));
+    }
+
+    #[test]
+    fn format_benchmarks_per_year() {
+        let mut older = get_mock_timings();
+        older.data.truncate(1);
+
+        let mut s = format!("{}{}", MARKER, MARKER);
+        update_content(
+            &mut s,
+            vec![(year!(2024), get_mock_timings()), (year!(2023), older)],
+        )
+        .unwrap();
+
+        assert_eq!(s.matches("## Benchmarks").count(), 1);
+        let pos_2024 = s.find("### 2024").unwrap();
+        let pos_2023 = s.find("### 2023").unwrap();
+        assert!(pos_2024 < pos_2023);
+        assert!(s.contains("| [Day 1](./src/bin/2023_01.rs) | `10ms` | `20ms` |"));
+        assert!(s.contains("**Total: 190000.00ms**"));
+        assert!(s.contains("**Total: 30000.00ms**"));
+    }
 }
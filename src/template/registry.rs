@@ -0,0 +1,61 @@
+/// A dispatch table of solutions compiled into the CLI binary.
+/// The table is generated by `build.rs` and installed by `src/main.rs` when the `registry` feature is enabled.
+/// Registered days are run in-process by `cargo all`, `cargo time` and `cargo verify`,
+/// all other days are run by spawning their binary.
+use std::sync::OnceLock;
+
+use crate::template::bench::BenchConfig;
+use crate::template::protocol::Report;
+use crate::template::{Day, Year};
+
+/// Runs all parts of a solution on `input`, passing every report to the callback.
+pub type RunFn = fn(input: &str, bench: Option<&BenchConfig>, on_report: &mut dyn FnMut(Report));
+
+/// A solution registered for in-process execution.
+#[derive(Clone, Copy)]
+pub struct Entry {
+    pub year: Year,
+    pub day: Day,
+    pub run: RunFn,
+}
+
+static REGISTRY: OnceLock<&'static [Entry]> = OnceLock::new();
+
+/// Installs the dispatch table. Only the first call has an effect.
+pub fn register(entries: &'static [Entry]) {
+    let _ = REGISTRY.set(entries);
+}
+
+/// Returns the registered solution of a day, if any.
+pub fn get(year: Year, day: Day) -> Option<&'static Entry> {
+    REGISTRY
+        .get()?
+        .iter()
+        .find(|entry| entry.year == year && entry.day == day)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{get, register, Entry};
+    use crate::template::bench::BenchConfig;
+    use crate::template::protocol::Report;
+    use crate::{day, year};
+
+    fn run(_input: &str, _bench: Option<&BenchConfig>, _on_report: &mut dyn FnMut(Report)) {}
+
+    static ENTRIES: &[Entry] = &[Entry {
+        year: year!(2024),
+        day: day!(3),
+        run,
+    }];
+
+    #[test]
+    fn finds_registered_days() {
+        register(ENTRIES);
+        assert!(get(year!(2024), day!(3)).is_some());
+        assert!(get(year!(2024), day!(4)).is_none());
+        assert!(get(year!(2023), day!(3)).is_none());
+    }
+}
@@ -0,0 +1,224 @@
+/// Compares fresh benchmark timings with stored ones.
+use std::{fmt::Display, time::Duration};
+
+use crate::template::timings::{Timing, Timings};
+use crate::template::Day;
+
+/// Relative change above which a step counts as regressed or improved.
+pub const DEFAULT_THRESHOLD: f64 = 0.1;
+
+/// A timed step of a solution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Parse,
+    Part(u8),
+}
+
+impl Step {
+    /// Time of this step in nanoseconds, if it was timed.
+    pub fn nanos(self, timing: &Timing) -> Option<f64> {
+        match self {
+            Step::Parse => timing.parse_nanos(),
+            Step::Part(part) => timing.part_nanos(part),
+        }
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Step::Parse => write!(f, "parse"),
+            Step::Part(part) => write!(f, "part {part}"),
+        }
+    }
+}
+
+/// The change of a single step between the stored and the fresh timings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub day: Day,
+    pub step: Step,
+    pub old_nanos: f64,
+    /// Fresh time of the step, `None` if it no longer produced a result.
+    pub new_nanos: Option<f64>,
+}
+
+impl Change {
+    /// Relative change, e.g. `0.25` if the step got 25% slower.
+    pub fn relative(&self) -> Option<f64> {
+        self.new_nanos
+            .map(|new_nanos| (new_nanos - self.old_nanos) / self.old_nanos)
+    }
+}
+
+impl Display for Change {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let old = Duration::from_nanos(self.old_nanos as u64);
+        match (self.new_nanos, self.relative()) {
+            (Some(new_nanos), Some(relative)) => write!(
+                f,
+                "Day {} {}: {old:.1?} → {:.1?} ({:+.1}%)",
+                self.day,
+                self.step,
+                Duration::from_nanos(new_nanos as u64),
+                relative * 100.0
+            ),
+            _ => write!(f, "Day {} {}: {old:.1?} → no result", self.day, self.step),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Comparison {
+    pub regressions: Vec<Change>,
+    pub improvements: Vec<Change>,
+}
+
+impl Comparison {
+    pub fn has_regressions(&self) -> bool {
+        !self.regressions.is_empty()
+    }
+}
+
+/// Compares every step of `fresh` that was timed in `stored`.
+/// Changes within `threshold` (relative to the stored time) are considered noise.
+/// A part without a fresh time, e.g. because it is unsolved, failed or timed out, counts as regressed.
+pub fn compare(stored: &Timings, fresh: &Timings, threshold: f64) -> Comparison {
+    let mut comparison = Comparison::default();
+
+    for new in &fresh.data {
+        let Some(old) = stored.data.iter().find(|t| t.day == new.day) else {
+            continue;
+        };
+
+        for step in [Step::Parse, Step::Part(1), Step::Part(2)] {
+            let Some(old_nanos) = step.nanos(old) else {
+                continue;
+            };
+            let new_nanos = step.nanos(new);
+
+            // parsing is only timed on its own for days implementing `Solution`.
+            if old_nanos <= 0.0 || (new_nanos.is_none() && step == Step::Parse) {
+                continue;
+            }
+
+            let change = Change {
+                day: new.day,
+                step,
+                old_nanos,
+                new_nanos,
+            };
+
+            match change.relative() {
+                Some(relative) if relative < -threshold => comparison.improvements.push(change),
+                Some(relative) if relative <= threshold => {}
+                _ => comparison.regressions.push(change),
+            }
+        }
+    }
+
+    comparison
+}
+
+/// Parses a threshold given in percent, e.g. `10` or `10%`.
+pub fn parse_threshold(s: &str) -> Option<f64> {
+    let percent: f64 = s.trim().trim_end_matches('%').parse().ok()?;
+    if percent.is_finite() && percent >= 0.0 {
+        Some(percent / 100.0)
+    } else {
+        None
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{compare, parse_threshold, Step};
+    use crate::day;
+    use crate::template::timings::{Timing, Timings};
+    use crate::template::Day;
+
+    fn timing(day: u8, part_1: Option<&str>, part_2: Option<&str>) -> Timing {
+        Timing {
+            part_1: part_1.map(Into::into),
+            part_2: part_2.map(Into::into),
+            ..Timing::new(Day::new(day).unwrap())
+        }
+    }
+
+    #[test]
+    fn finds_regressions_and_improvements() {
+        let stored = Timings {
+            data: vec![
+                timing(1, Some("1.0ms"), Some("2.0ms")),
+                timing(2, Some("10.0µs"), Some("1.0s")),
+            ],
+        };
+        let fresh = Timings {
+            data: vec![
+                timing(1, Some("1.5ms"), Some("2.1ms")),
+                timing(2, Some("5.0µs"), Some("1.05s")),
+                timing(3, Some("1.0s"), None),
+            ],
+        };
+
+        let comparison = compare(&stored, &fresh, 0.1);
+
+        assert!(comparison.has_regressions());
+        assert_eq!(comparison.regressions.len(), 1);
+        assert_eq!(comparison.regressions[0].day, day!(1));
+        assert_eq!(comparison.regressions[0].step, Step::Part(1));
+        assert_eq!(
+            comparison.regressions[0].to_string(),
+            "Day 01 part 1: 1.0ms → 1.5ms (+50.0%)"
+        );
+
+        assert_eq!(comparison.improvements.len(), 1);
+        assert_eq!(comparison.improvements[0].day, day!(2));
+        assert_eq!(comparison.improvements[0].step, Step::Part(1));
+    }
+
+    #[test]
+    fn reports_parts_without_results() {
+        let stored = Timings {
+            data: vec![timing(1, Some("1.0ms"), Some("2.0ms"))],
+        };
+        let fresh = Timings {
+            data: vec![timing(1, Some("1.0ms"), None)],
+        };
+
+        let comparison = compare(&stored, &fresh, 0.1);
+        assert_eq!(comparison.regressions.len(), 1);
+        assert_eq!(comparison.regressions[0].step, Step::Part(2));
+        assert_eq!(
+            comparison.regressions[0].to_string(),
+            "Day 01 part 2: 2.0ms → no result"
+        );
+    }
+
+    #[test]
+    fn compares_parse_times() {
+        let mut old = timing(1, Some("1.0ms"), None);
+        old.parse = Some("1.0ms".into());
+        let mut new = old.clone();
+        new.parse = Some("3.0ms".into());
+
+        let comparison = compare(
+            &Timings { data: vec![old] },
+            &Timings { data: vec![new] },
+            0.5,
+        );
+        assert_eq!(comparison.regressions.len(), 1);
+        assert_eq!(comparison.regressions[0].step, Step::Parse);
+    }
+
+    #[test]
+    fn parses_thresholds() {
+        assert_eq!(parse_threshold("10"), Some(0.1));
+        assert_eq!(parse_threshold("25%"), Some(0.25));
+        assert_eq!(parse_threshold("-5"), None);
+        assert_eq!(parse_threshold("lots"), None);
+    }
+}
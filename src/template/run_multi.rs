@@ -1,51 +1,358 @@
-use std::{collections::HashSet, io};
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::{self, Write},
+    process::{Child, ChildStderr, ChildStdout, Command, ExitStatus},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::template::bench::BenchConfig;
+use crate::template::input::InputSource;
+use crate::template::protocol::SolutionReport;
+use crate::template::{Day, Year, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
 use super::{
     all_days,
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
-    let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+/// Runs a set of days of a year in order. Days are benched if a `bench` configuration is passed.
+/// With more than one job, days run concurrently and their output is printed in day order once complete.
+pub fn run_multi(
+    year: Year,
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    bench: Option<&BenchConfig>,
+    jobs: usize,
+    timeouts: &Timeouts,
+) -> MultiRun {
+    // NOTE: use non-duplicate, sorted day values.
+    let days: Vec<Day> = all_days(year)
+        .filter(|day| days_to_run.contains(day))
+        .collect();
+
+    let jobs = if bench.is_some() && jobs > 1 {
+        eprintln!(
+            "Benchmarks run one day at a time to keep timings clean, ignoring `--jobs {jobs}`."
+        );
+        1
+    } else {
+        jobs.clamp(1, days.len().max(1))
+    };
 
+    let mut timings: Vec<Timing> = Vec::with_capacity(days.len());
+    let mut timed_out: Vec<(Day, u8)> = vec![];
+    let mut failed: Vec<(Day, u8)> = vec![];
+    let mut crashed: Vec<(Day, String)> = vec![];
     let mut need_space = false;
 
-    // NOTE: use non-duplicate, sorted day values.
-    all_days()
-        .filter(|day| days_to_run.contains(day))
-        .for_each(|day| {
+    let mut on_result = |day: Day, output: &SolutionReport| {
+        if let Some(reason) = &output.crashed {
+            crashed.push((day, reason.clone()));
+        }
+        if !output.is_empty() {
+            let timing = child_commands::parse_exec_time(output, day);
+            timed_out.extend(timing.timed_out.iter().map(|part| (day, *part)));
+            failed.extend(timing.failed.iter().map(|part| (day, *part)));
+            timings.push(timing);
+        }
+    };
+
+    if jobs == 1 {
+        for day in days {
             if need_space {
                 println!();
             }
             need_space = true;
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
+            let mut stdout = io::stdout();
+            let output = run_day(
+                year,
+                day,
+                bench,
+                is_release,
+                timeouts,
+                &InputSource::Puzzle,
+                true,
+                &mut stdout,
+            );
+            on_result(day, &output);
+        }
+    } else {
+        let wall_time = Instant::now();
+        let mut day_time = Duration::ZERO;
+
+        let results = run_parallel(
+            year,
+            &days,
+            is_release,
+            jobs,
+            *timeouts,
+            InputSource::Puzzle,
+        );
+        for (day, buffer, output, elapsed) in results {
+            if need_space {
+                println!();
+            }
+            need_space = true;
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(&buffer);
+            let _ = stdout.flush();
 
-            if output.is_empty() {
-                println!("Not solved.");
-            } else {
-                let val = child_commands::parse_exec_time(&output, day);
-                timings.push(val);
+            day_time += elapsed;
+            on_result(day, &output);
+        }
+
+        let wall_time = wall_time.elapsed();
+        println!(
+            "\n{ANSI_BOLD}Wall time:{ANSI_RESET} {ANSI_ITALIC}{wall_time:.1?}{ANSI_RESET} · {ANSI_BOLD}Summed day time:{ANSI_RESET} {ANSI_ITALIC}{day_time:.1?}{ANSI_RESET} ({jobs} jobs)"
+        );
+    }
+
+    for (title, parts) in [("Timed out", &timed_out), ("Failed", &failed)] {
+        if !parts.is_empty() {
+            println!("\n{ANSI_BOLD}{title}:{ANSI_RESET}");
+            for (day, part) in parts {
+                println!("  Day {day} part {part}");
             }
-        });
+        }
+    }
 
-    if is_timed {
+    if !crashed.is_empty() {
+        println!("\n{ANSI_BOLD}Crashed:{ANSI_RESET}");
+        for (day, reason) in &crashed {
+            println!("  Day {day}: {reason}");
+        }
+    }
+
+    let has_failures = !failed.is_empty() || !timed_out.is_empty() || !crashed.is_empty();
+
+    let timings = bench.map(|_| {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
         println!(
             "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
         );
-        Some(timings)
-    } else {
-        None
+        timings
+    });
+
+    MultiRun {
+        timings,
+        has_failures,
     }
 }
 
+/// What [`run_multi`] collected.
+pub struct MultiRun {
+    /// Timings of all days, present if they were benched.
+    pub timings: Option<Timings>,
+    /// Whether a part failed or timed out, or a day crashed.
+    pub has_failures: bool,
+}
+
+/// Runs a single day, writing its output to `out`.
+/// See [`child_commands::run_solution`] for when a day may run `in_process`.
+#[allow(clippy::too_many_arguments)]
+fn run_day(
+    year: Year,
+    day: Day,
+    bench: Option<&BenchConfig>,
+    is_release: bool,
+    timeouts: &Timeouts,
+    input: &InputSource,
+    in_process: bool,
+    out: &mut dyn Write,
+) -> SolutionReport {
+    let _ = writeln!(out, "{ANSI_BOLD}Day {day}{ANSI_RESET}");
+    let _ = writeln!(out, "------");
+
+    let output = child_commands::run_solution(
+        year, day, bench, is_release, timeouts, input, in_process, out,
+    )
+    .unwrap();
+
+    if let Some(reason) = &output.crashed {
+        let _ = writeln!(out, "{ANSI_BOLD}Crashed:{ANSI_RESET} {reason}");
+    } else if output.is_empty() {
+        let _ = writeln!(out, "Not solved.");
+    }
+
+    output
+}
+
+/// Runs days on `jobs` worker threads. Yields the buffered output of every day in day order,
+/// as soon as the day and all days before it have completed.
+/// Every day is spawned, as anything a day prints in-process would bypass its buffer.
+fn run_parallel(
+    year: Year,
+    days: &[Day],
+    is_release: bool,
+    jobs: usize,
+    timeouts: Timeouts,
+    input: InputSource,
+) -> impl Iterator<Item = (Day, Vec<u8>, SolutionReport, Duration)> {
+    let (sender, receiver) = mpsc::channel();
+    let days = days.to_vec();
+    let next = AtomicUsize::new(0);
+
+    thread::spawn(move || {
+        thread::scope(|scope| {
+            for _ in 0..jobs {
+                let sender = sender.clone();
+                let (days, next, input) = (&days, &next, &input);
+                scope.spawn(move || loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(day) = days.get(index) else {
+                        break;
+                    };
+
+                    let timer = Instant::now();
+                    let mut buffer = vec![];
+                    let output = run_day(
+                        year,
+                        *day,
+                        None,
+                        is_release,
+                        &timeouts,
+                        input,
+                        false,
+                        &mut buffer,
+                    );
+
+                    if sender
+                        .send((index, (*day, buffer, output, timer.elapsed())))
+                        .is_err()
+                    {
+                        break;
+                    }
+                });
+            }
+        });
+    });
+
+    // re-order results, holding back days that finished before an earlier one.
+    let mut pending = BTreeMap::new();
+    let mut next_index = 0;
+
+    std::iter::from_fn(move || loop {
+        if let Some(result) = pending.remove(&next_index) {
+            next_index += 1;
+            return Some(result);
+        }
+        let (index, result) = receiver.recv().ok()?;
+        pending.insert(index, result);
+    })
+}
+
+/// Limits on how long a spawned solution may run before it is killed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Timeouts {
+    /// Maximum time of a single step, i.e. parsing or one part.
+    pub part: Option<Duration>,
+    /// Maximum time of all steps of a day.
+    pub day: Option<Duration>,
+}
+
+impl Timeouts {
+    pub fn is_set(&self) -> bool {
+        self.part.is_some() || self.day.is_some()
+    }
+
+    /// The point in time at which a run that started at `started` is cancelled.
+    /// `step` is the start of the current step's executions and their number, if one is running.
+    fn deadline(&self, started: Instant, step: Option<(Instant, u32)>) -> Option<Instant> {
+        let part = step
+            .zip(self.part)
+            .and_then(|((step_started, runs), timeout)| {
+                step_started.checked_add(timeout.saturating_mul(runs))
+            });
+        [
+            part,
+            self.day.and_then(|timeout| started.checked_add(timeout)),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+}
+
+/// Children that are currently running, so that they can be killed on Ctrl-C.
+static RUNNING_CHILDREN: Mutex<Vec<Arc<Mutex<Child>>>> = Mutex::new(vec![]);
+
+/// A spawned solution that is killed by [`kill_running_children`] while it runs.
+struct RunningChild(Arc<Mutex<Child>>);
+
+impl RunningChild {
+    fn track(child: Child) -> Self {
+        let child = Arc::new(Mutex::new(child));
+        lock(&RUNNING_CHILDREN).push(Arc::clone(&child));
+        Self(child)
+    }
+
+    fn take_stdout(&self) -> Option<ChildStdout> {
+        lock(&self.0).stdout.take()
+    }
+
+    fn take_stderr(&self) -> Option<ChildStderr> {
+        lock(&self.0).stderr.take()
+    }
+
+    fn kill(&self) {
+        kill_child(&mut lock(&self.0));
+    }
+
+    /// Waits for the child to exit without holding its lock, so that it can be killed meanwhile.
+    fn wait(&self) -> io::Result<ExitStatus> {
+        loop {
+            if let Some(status) = lock(&self.0).try_wait()? {
+                return Ok(status);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+impl Drop for RunningChild {
+    fn drop(&mut self) {
+        lock(&RUNNING_CHILDREN).retain(|child| !Arc::ptr_eq(child, &self.0));
+    }
+}
+
+/// Kills every solution that is still running. Used by the Ctrl-C handler of the runner.
+pub fn kill_running_children() {
+    for child in lock(&RUNNING_CHILDREN).iter() {
+        kill_child(&mut lock(child));
+    }
+}
+
+/// Kills a child together with its process group.
+/// `cargo run` does not forward signals, so killing cargo alone would leave the solution running.
+fn kill_child(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: sends a signal to the process group the child leads, see `spawn_in_group`.
+        unsafe { libc::kill(-pid, libc::SIGKILL) };
+    }
+    let _ = child.kill();
+}
+
+/// Spawns a command as the leader of a new process group, which [`kill_child`] kills as a whole.
+fn spawn_in_group(cmd: &mut Command) -> io::Result<Child> {
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(cmd, 0);
+    cmd.spawn()
+}
+
+/// Locks a mutex, ignoring that a panicking thread held it before.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum Error {
@@ -60,137 +367,424 @@ impl From<std::io::Error> for Error {
 }
 
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn get_path_for_bin(year: Year, day: Day) -> String {
+    format!("./src/bin/{}.rs", get_bin_name(year, day))
+}
+
+/// Name of the binary of a solution, e.g. `2024_01`.
+#[must_use]
+pub fn get_bin_name(year: Year, day: Day) -> String {
+    format!("{year}_{day}")
 }
 
 /// All solutions live in isolated binaries.
-/// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
+/// This module encapsulates interaction with these binaries, both invoking them as well as collecting their reports.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::{get_bin_name, get_path_for_bin, spawn_in_group, Error, RunningChild, Timeouts};
+    use crate::template::bench::BenchConfig;
+    use crate::template::input::InputSource;
+    use crate::template::memory::MEMORY_FLAG;
+    use crate::template::protocol::{
+        PartReport, PartStatus, Progress, Report, SolutionReport, JSON_FLAG,
+    };
+    use crate::template::registry::{self, Entry};
+    use crate::template::runner::{catch_panic, format_duration, format_result, format_stats_line};
+    use crate::template::{Day, Year};
     use std::{
-        io::{BufRead, BufReader},
+        env,
+        io::{BufRead, BufReader, Write},
         path::Path,
         process::{Command, Stdio},
+        sync::mpsc::{self, RecvTimeoutError},
         thread,
+        time::Instant,
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Hidden subcommand of the runner that runs a single registered day, see [`print_registered`].
+    pub const REGISTERED_COMMAND: &str = "__run-registered";
+
+    /// Run the solution for a given day on `input`, in-process if it is registered and `in_process` is set,
+    /// or by spawning it otherwise. Days are always spawned when a timeout is set, so that they can be killed once it expires.
+    /// The results are written to `out` as they come in.
+    #[allow(clippy::too_many_arguments)]
+    pub fn run_solution(
+        year: Year,
+        day: Day,
+        bench: Option<&BenchConfig>,
+        is_release: bool,
+        timeouts: &Timeouts,
+        input: &InputSource,
+        in_process: bool,
+        out: &mut dyn Write,
+    ) -> Result<SolutionReport, Error> {
         // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+        if !Path::new(&get_path_for_bin(year, day)).exists() {
+            return Ok(SolutionReport::default());
         }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+        let registered = registry::get(year, day);
 
-        if is_release {
-            args.push("--release");
+        if let Some(entry) = registered {
+            if in_process && !timeouts.is_set() {
+                return Ok(run_registered(entry, bench, input, out));
+            }
         }
 
-        if is_timed {
+        let mut cmd = if registered.is_some() {
+            // registered days run in a fresh process of this binary, which needs no extra build.
+            let mut cmd = Command::new(env::current_exe()?);
+            cmd.args([REGISTERED_COMMAND, &year.to_string(), &day.to_string()]);
+            cmd
+        } else {
+            let bin_name = get_bin_name(year, day);
+            let mut build_args = vec!["build", "--quiet", "--bin", &bin_name];
+            if is_release {
+                build_args.push("--release");
+            }
+
+            // build upfront, so that compiling does not count towards the timeouts.
+            if timeouts.is_set() && !Command::new("cargo").args(&build_args).status()?.success() {
+                return Ok(SolutionReport {
+                    crashed: Some("failed to build".into()),
+                    ..SolutionReport::default()
+                });
+            }
+
+            let mut cmd = Command::new("cargo");
+            cmd.args(["run", "--quiet", "--bin", &bin_name]);
+            if is_release {
+                cmd.arg("--release");
+            }
+            cmd.arg("--");
+            cmd
+        };
+
+        // ask the child to report results as JSON lines.
+        cmd.arg(JSON_FLAG);
+        cmd.args(input.to_args());
+
+        if bench.is_some() {
             // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
+            cmd.arg("--time");
         }
 
         // spawn child command with piped stdout/stderr.
-        // forward output to stdout/stderr while grabbing stdout lines.
+        // forward output to stdout/stderr while collecting part reports.
 
-        let mut cmd = Command::new("cargo")
-            .args(&args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
+        let child = spawn_in_group(
+            cmd.envs(bench.map(BenchConfig::to_env).unwrap_or_default())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped()),
+        )?;
 
-        let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
-        let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
+        let child = RunningChild::track(child);
 
-        let mut output = vec![];
+        let stdout = BufReader::new(child.take_stdout().ok_or(super::Error::BrokenPipe)?);
+        let stderr = BufReader::new(child.take_stderr().ok_or(super::Error::BrokenPipe)?);
 
-        let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
+        let stderr_thread = thread::spawn(move || {
+            stderr.lines().map_while(Result::ok).for_each(|line| {
+                eprintln!("{line}");
             });
         });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
+        // read lines on a separate thread, so that waiting for them can time out.
+        let (sender, receiver) = mpsc::channel();
+        let stdout_thread = thread::spawn(move || {
+            for line in stdout.lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut reports = SolutionReport::default();
+
+        let started = Instant::now();
+        let mut step_started = started;
+        // the part timeout applies to single executions, which the child announces as it goes.
+        let mut step = None;
+
+        loop {
+            let deadline = timeouts.deadline(started, step);
+
+            let line = match deadline {
+                None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                Some(deadline) => {
+                    receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                }
+            };
+
+            match line {
+                Ok(line) => {
+                    if let Some(progress) = Progress::from_json_line(&line) {
+                        step = Some(match progress {
+                            Progress::Started => {
+                                step_started = Instant::now();
+                                (step_started, 1)
+                            }
+                            Progress::Benching { runs } => (Instant::now(), runs),
+                        });
+                    } else if let Some(report) = Report::from_json_line(&line) {
+                        write_report(out, &report);
+                        reports.push(report);
+                        step = None;
+                    } else {
+                        let _ = writeln!(out, "{line}");
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    child.kill();
+                    if let Some(report) = timed_out_report(&reports, step_started) {
+                        let report = Report::Part(report);
+                        write_report(out, &report);
+                        reports.push(report);
+                    }
+                    break;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        stdout_thread.join().unwrap();
+        stderr_thread.join().unwrap();
+        let status = child.wait()?;
+
+        // failed parts make the child exit with an error as well, but were reported already.
+        let is_explained = reports
+            .parts
+            .iter()
+            .any(|report| matches!(report.status, PartStatus::Failed | PartStatus::TimedOut));
+        if !status.success() && !is_explained {
+            reports.crashed = Some(match status.code() {
+                Some(code) => format!("exited with code {code}"),
+                // e.g. killed by a signal.
+                None => status.to_string(),
+            });
+        }
+
+        Ok(reports)
+    }
+
+    /// Runs a solution binary built with the `dhat-heap` feature, which reports the heap usage of every part.
+    /// The output of the solution is not forwarded, it was already shown by the timed run.
+    pub fn run_heap_profile(year: Year, day: Day) -> Result<SolutionReport, Error> {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--quiet",
+                "--profile",
+                "dhat",
+                "--features",
+                "dhat-heap",
+            ])
+            .args([
+                "--bin",
+                &get_bin_name(year, day),
+                "--",
+                JSON_FLAG,
+                MEMORY_FLAG,
+            ])
+            .stderr(Stdio::inherit())
+            .output()?;
+
+        let mut reports = SolutionReport::default();
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(Report::from_json_line)
+            .for_each(|report| reports.push(report));
+
+        Ok(reports)
+    }
+
+    /// Reports the part that was running when a timeout expired, i.e. the one after the last reported part.
+    fn timed_out_report(reports: &SolutionReport, step_started: Instant) -> Option<PartReport> {
+        let part = reports.parts.last().map_or(1, |report| report.part + 1);
+        (part <= 2).then(|| PartReport {
+            part,
+            answer: None,
+            #[allow(clippy::cast_precision_loss)]
+            nanos: step_started.elapsed().as_nanos() as f64,
+            samples: 0,
+            status: PartStatus::TimedOut,
+            stats: None,
+            memory: None,
+            error: vec![],
+        })
+    }
+
+    /// Runs a registered day on `input` and prints its reports as JSON lines.
+    /// This is the entry point of the processes spawned by [`run_solution`] for registered days.
+    pub fn print_registered(
+        year: Year,
+        day: Day,
+        bench: Option<&BenchConfig>,
+        input: &InputSource,
+    ) {
+        let Some(entry) = registry::get(year, day) else {
+            eprintln!("Day {day} of {year} is not registered.");
+            return;
+        };
+
+        let Some(input) = read_input(entry, input) else {
+            return;
+        };
+
+        (entry.run)(&input, bench, &mut |report| {
+            let line = match &report {
+                Report::Parse(report) => report.to_json_line(),
+                Report::Part(report) => report.to_json_line(),
+            };
             println!("{line}");
-            output.push(line);
+        });
+    }
+
+    fn read_input(entry: &Entry, input: &InputSource) -> Option<String> {
+        let input = input.read(entry.year, entry.day).ok();
+        if input.is_none() {
+            eprintln!("Could not open input file for day {}.", entry.day);
         }
+        input
+    }
+
+    /// Call the parts of a registered solution directly.
+    fn run_registered(
+        entry: &Entry,
+        bench: Option<&BenchConfig>,
+        input: &InputSource,
+        out: &mut dyn Write,
+    ) -> SolutionReport {
+        let mut reports = SolutionReport::default();
+
+        let Some(input) = read_input(entry, input) else {
+            return reports;
+        };
 
-        thread.join().unwrap();
-        cmd.wait()?;
+        // parts catch their own panics, this catches panics while parsing.
+        let result = catch_panic(|| {
+            (entry.run)(&input, bench, &mut |report| {
+                write_report(out, &report);
+                reports.push(report);
+            });
+        });
 
-        Ok(output)
+        reports.crashed = result.err();
+        reports
     }
 
-    pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
-        let mut timings = super::Timing {
-            day,
-            part_1: None,
-            part_2: None,
-            total_nanos: 0_f64,
+    /// Write a report the same way a solution prints its result in interactive mode.
+    fn write_report(out: &mut dyn Write, report: &Report) {
+        let (line, stats) = match report {
+            Report::Parse(report) => (
+                format!(
+                    "Parse:{}\n",
+                    format_duration(&report.duration(), report.samples)
+                ),
+                &report.stats,
+            ),
+            Report::Part(report) if report.status == PartStatus::TimedOut => (
+                format!(
+                    "Part {}: timed out after {:.1?}\n",
+                    report.part,
+                    report.duration()
+                ),
+                &report.stats,
+            ),
+            Report::Part(report) => {
+                let part_str = format!("Part {}", report.part);
+                let duration_str = format_duration(&report.duration(), report.samples);
+                (
+                    format_result(
+                        &report.outcome(),
+                        &part_str,
+                        &duration_str,
+                        report.memory.as_ref(),
+                    ),
+                    &report.stats,
+                )
+            }
         };
 
-        output
-            .iter()
-            .filter_map(|l| {
-                if !l.contains(" samples)") {
-                    return None;
-                }
+        // clear a pending ` > benching` from the current line.
+        let _ = write!(out, "\r{line}");
+
+        if let Some(stats) = stats {
+            let _ = writeln!(out, "{}", format_stats_line(stats));
+        }
+
+        let _ = out.flush();
+    }
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
-                    eprintln!("Could not parse timings from line: {l}");
-                    return None;
-                };
-
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
-            })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
+    pub fn parse_exec_time(reports: &SolutionReport, day: Day) -> super::Timing {
+        let mut timings = super::Timing::new(day);
+
+        let parts_with = |status: PartStatus| {
+            reports
+                .parts
+                .iter()
+                .filter(|report| report.status == status)
+                .map(|report| report.part)
+                .collect()
+        };
+        timings.timed_out = parts_with(PartStatus::TimedOut);
+        timings.failed = parts_with(PartStatus::Failed);
+
+        reports
+            .parts
+            .iter()
+            .filter(|report| report.answer.is_some())
+            .for_each(|report| {
+                let timing_str = format!("{:.1?}", report.duration());
+
+                match report.part {
+                    1 => {
+                        timings.part_1 = Some(timing_str);
+                        timings.part_1_stats.clone_from(&report.stats);
+                        timings.part_1_memory = report.memory;
+                    }
+                    2 => {
+                        timings.part_2 = Some(timing_str);
+                        timings.part_2_stats.clone_from(&report.stats);
+                        timings.part_2_memory = report.memory;
+                    }
+                    _ => return,
                 }
 
-                timings.total_nanos += nanos;
+                timings.total_nanos += report.nanos;
             });
 
+        // parsing only counts towards the total if at least one part was solved.
+        if let Some(report) = &reports.parse {
+            if timings.part_1.is_some() || timings.part_2.is_some() {
+                timings.parse = Some(format!("{:.1?}", report.duration()));
+                timings.parse_stats.clone_from(&report.stats);
+                timings.total_nanos += report.nanos;
+            }
+        }
+
         timings
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
+    /// Returns the answer reported for a part, if it was solved.
+    pub fn find_answer(reports: &[PartReport], part: u8) -> Option<String> {
+        reports
+            .iter()
+            .find(|report| report.part == part)
+            .and_then(|report| report.answer.clone())
     }
+}
 
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
-            .split(" samples)")
-            .next()?
-            .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::{Duration, Instant};
 
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
-    }
+    use super::child_commands::{find_answer, parse_exec_time};
+    use super::{run_parallel, Timeouts};
+    use crate::template::input::InputSource;
+    use crate::template::protocol::{ParseReport, PartReport, PartStatus, SolutionReport};
+    use crate::{day, year};
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
-    #[cfg(feature = "test_lib")]
     macro_rules! assert_approx_eq {
         ($a:expr, $b:expr) => {{
             let (a, b) = (&$a, &$b);
@@ -203,55 +797,193 @@ pub mod child_commands {
         }};
     }
 
-    #[cfg(feature = "test_lib")]
-    mod tests {
-        use super::parse_exec_time;
-
-        use crate::day;
-
-        #[test]
-        fn parses_execution_times() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
-                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+    fn report(part: u8, answer: Option<&str>, nanos: f64, samples: u128) -> PartReport {
+        PartReport {
+            part,
+            answer: answer.map(Into::into),
+            nanos,
+            samples,
+            status: if answer.is_some() {
+                PartStatus::Solved
+            } else {
+                PartStatus::Unsolved
+            },
+            stats: None,
+            memory: None,
+            error: vec![],
         }
+    }
 
-        #[test]
-        fn parses_with_patterns_in_input() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: @ @ @ ( ) ms (2s @ 5 samples)".into(),
-                    "Part 2: 10s (100ms @ 1 samples)".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+    fn reports(parts: Vec<PartReport>) -> SolutionReport {
+        SolutionReport {
+            parse: None,
+            parts,
+            crashed: None,
         }
+    }
 
-        #[test]
-        fn parses_missing_parts() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: ✖        ".into(),
-                    "Part 2: ✖        ".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 0_f64);
-            assert_eq!(res.part_1.is_none(), true);
-            assert_eq!(res.part_2.is_none(), true);
+    #[test]
+    fn parses_execution_times() {
+        let res = parse_exec_time(
+            &reports(vec![
+                report(1, Some("0"), 74.0, 100_000),
+                report(2, Some("10"), 74_130_000.0, 99_999),
+            ]),
+            day!(1),
+        );
+        assert_approx_eq!(res.total_nanos, 74130074_f64);
+        assert_eq!(res.part_1.unwrap(), "74.0ns");
+        assert_eq!(res.part_2.unwrap(), "74.1ms");
+    }
+
+    #[test]
+    fn parses_with_patterns_in_input() {
+        let res = parse_exec_time(
+            &reports(vec![
+                report(1, Some("@ @ @ ( ) ms (2s @ 5 samples)"), 2e9, 5),
+                report(2, Some("10s"), 1e8, 1),
+            ]),
+            day!(1),
+        );
+        assert_approx_eq!(res.total_nanos, 2100000000_f64);
+        assert_eq!(res.part_1.unwrap(), "2.0s");
+        assert_eq!(res.part_2.unwrap(), "100.0ms");
+    }
+
+    #[test]
+    fn parses_missing_parts() {
+        let res = parse_exec_time(
+            &reports(vec![report(1, None, 10.0, 1), report(2, None, 10.0, 1)]),
+            day!(1),
+        );
+        assert_approx_eq!(res.total_nanos, 0_f64);
+        assert_eq!(res.part_1.is_none(), true);
+        assert_eq!(res.part_2.is_none(), true);
+    }
+
+    #[test]
+    fn parses_failed_parts() {
+        let mut failed = report(1, None, 10.0, 1);
+        failed.status = PartStatus::Failed;
+        failed.error = vec!["invalid number".into()];
+
+        let res = parse_exec_time(
+            &reports(vec![failed, report(2, Some("10"), 10.0, 1)]),
+            day!(1),
+        );
+        assert_eq!(res.failed, vec![1]);
+        assert!(res.timed_out.is_empty());
+        assert_eq!(res.part_2.unwrap(), "10.0ns");
+    }
+
+    #[test]
+    fn parses_parse_times() {
+        let mut solved = reports(vec![report(1, Some("1"), 2000.0, 1)]);
+        solved.parse = Some(ParseReport {
+            nanos: 500.0,
+            samples: 1,
+            stats: None,
+        });
+
+        let res = parse_exec_time(&solved, day!(1));
+        assert_approx_eq!(res.total_nanos, 2500_f64);
+        assert_eq!(res.parse.unwrap(), "500.0ns");
+
+        let mut unsolved = reports(vec![report(1, None, 2000.0, 1)]);
+        unsolved.parse = solved.parse;
+
+        let res = parse_exec_time(&unsolved, day!(1));
+        assert_approx_eq!(res.total_nanos, 0_f64);
+        assert_eq!(res.parse, None);
+    }
+
+    #[test]
+    fn finds_answers() {
+        let reports = [report(1, Some("#..\n.#."), 1.0, 1), report(2, None, 1.0, 1)];
+        assert_eq!(find_answer(&reports, 1), Some("#..\n.#.".into()));
+        assert_eq!(find_answer(&reports, 2), None);
+    }
+
+    #[test]
+    fn picks_the_earliest_deadline() {
+        let started = Instant::now();
+        let step = Some((started + Duration::from_secs(5), 1));
+
+        let timeouts = Timeouts {
+            part: Some(Duration::from_secs(2)),
+            day: Some(Duration::from_secs(6)),
+        };
+        assert_eq!(
+            timeouts.deadline(started, step),
+            Some(started + Duration::from_secs(6))
+        );
+
+        let timeouts = Timeouts {
+            part: Some(Duration::from_secs(2)),
+            day: None,
+        };
+        assert_eq!(
+            timeouts.deadline(started, step),
+            Some(started + Duration::from_secs(7))
+        );
+
+        // benched steps get the part timeout for each of their runs.
+        let benching = Some((started, 13));
+        assert_eq!(
+            timeouts.deadline(started, benching),
+            Some(started + Duration::from_secs(26))
+        );
+
+        // between steps, e.g. while the process starts, only the day timeout applies.
+        assert_eq!(timeouts.deadline(started, None), None);
+
+        assert!(!Timeouts::default().is_set());
+        assert_eq!(Timeouts::default().deadline(started, step), None);
+    }
+
+    #[test]
+    fn yields_days_in_order() {
+        let days = [day!(22), day!(23), day!(24), day!(25)];
+        let results: Vec<_> = run_parallel(
+            year!(2024),
+            &days,
+            false,
+            3,
+            Timeouts::default(),
+            InputSource::Puzzle,
+        )
+        .collect();
+
+        assert_eq!(results.len(), 4);
+        for ((day, buffer, _, _), expected) in results.iter().zip(days) {
+            assert_eq!(*day, expected);
+            assert!(String::from_utf8_lossy(buffer).contains(&format!("Day {expected}")));
         }
     }
+
+    #[test]
+    fn buffers_what_days_print() {
+        // day 06 prints its board while solving, day 01 prints nothing but its answers.
+        let days = [day!(1), day!(6)];
+        let results: Vec<_> = run_parallel(
+            year!(2024),
+            &days,
+            false,
+            2,
+            Timeouts::default(),
+            InputSource::Example(None),
+        )
+        .collect();
+
+        let buffers: Vec<String> = results
+            .iter()
+            .map(|(_, buffer, _, _)| String::from_utf8_lossy(buffer).into_owned())
+            .collect();
+        assert_eq!(results[0].0, day!(1));
+        assert_eq!(results[1].0, day!(6));
+        assert!(!buffers[0].contains('#'));
+
+        let board = buffers[1].find("....#.....").unwrap();
+        assert!(buffers[1].find("Day 06").unwrap() < board);
+    }
 }
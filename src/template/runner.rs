@@ -1,65 +1,345 @@
 /// Encapsulates code that interacts with solution functions.
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::error::Error;
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
-use std::process::Output;
+use std::panic::{self, AssertUnwindSafe, PanicHookInfo};
+use std::sync::Once;
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{env, process};
 
+use crate::template::answers::Answers;
+use crate::template::aoc_client::{AocClient, AocCommandError};
+use crate::template::bench::{format_stats, BenchConfig, BenchStats};
+use crate::template::memory::{format_usage, HeapProfiler, HeapUsage};
+use crate::template::protocol::{
+    is_json_output, ParseReport, PartReport, PartStatus, Progress, Report,
+};
+use crate::template::submissions::{Submission, Submissions, Verdict};
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{Day, Solution, Year, ANSI_ITALIC, ANSI_RESET};
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// The outcome of running a part once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome<T> {
+    Solved(T),
+    Unsolved,
+    /// The error of the part followed by its sources, outermost first.
+    Failed(Vec<String>),
+}
+
+/// What a part can return: an [`Option`], or a [`Result`] for parts that can fail.
+/// Errors can be any [`Error`], including `Box<dyn Error>`, or a `String` / `&str` message.
+pub trait PartOutput<'a> {
+    type Answer: Display;
+
+    fn into_outcome(self) -> Outcome<Self::Answer>;
+}
+
+impl<T: Display> PartOutput<'_> for Option<T> {
+    type Answer = T;
+
+    fn into_outcome(self) -> Outcome<T> {
+        self.map_or(Outcome::Unsolved, Outcome::Solved)
+    }
+}
+
+impl<'a, T: Display, E: Into<Box<dyn Error + 'a>>> PartOutput<'a> for Result<T, E> {
+    type Answer = T;
+
+    fn into_outcome(self) -> Outcome<T> {
+        match self {
+            Ok(answer) => Outcome::Solved(answer),
+            Err(e) => Outcome::Failed(error_chain(&*e.into())),
+        }
+    }
+}
+
+/// Messages of an error and its sources, outermost first.
+fn error_chain(error: &dyn Error) -> Vec<String> {
+    let mut chain = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(e) = source {
+        chain.push(e.to_string());
+        source = e.source();
+    }
+    chain
+}
+
+thread_local! {
+    /// Whether panics on this thread are caught by [`catch_panic`].
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    /// Message and location of the last panic caught on this thread.
+    static CAUGHT_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+/// Runs `func` and returns the message and location of its panic, if it panics.
+/// The panic is not printed, other panics are reported by the previous hook as usual.
+pub(crate) fn catch_panic<T>(func: impl FnOnce() -> T) -> Result<T, String> {
+    INSTALL_PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CATCHING.get() {
+                CAUGHT_PANIC.set(Some(describe_panic(info)));
+            } else {
+                previous(info);
+            }
+        }));
+    });
+
+    let was_catching = CATCHING.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(func));
+    CATCHING.set(was_catching);
+
+    result.map_err(|payload| {
+        CAUGHT_PANIC
+            .take()
+            .unwrap_or_else(|| format!("panicked: {}", panic_message(&*payload)))
+    })
+}
+
+fn describe_panic(info: &PanicHookInfo) -> String {
+    let message = panic_message(info.payload());
+    match info.location() {
+        Some(location) => format!("panicked at {location}: {message}"),
+        None => format!("panicked: {message}"),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+/// Exits the process with a non-zero code if a part failed, so that runners can tell.
+pub fn exit_on_failure(statuses: &[PartStatus]) {
+    if statuses.contains(&PartStatus::Failed) {
+        process::exit(1);
+    }
+}
+
+/// Run a [`Solution`]: parse the input once, then run both parts on the parsed value.
+pub fn run_parsed<S: Solution>(input: &str, year: Year, day: Day) -> [PartStatus; 2] {
+    let is_json = is_json_output();
+    let bench = bench_config();
+
+    let (parsed, report) = measure_parse::<S>(input, bench.as_ref());
+
+    if is_json {
+        println!("{}", report.to_json_line());
+    } else {
+        println!(
+            "\rParse:{}",
+            format_duration(&report.duration(), report.samples)
+        );
+        if let Some(stats) = &report.stats {
+            print_stats(stats);
+        }
+    }
+
+    [
+        run_part(S::part_one, &parsed, year, day, 1),
+        run_part(S::part_two, &parsed, year, day, 2),
+    ]
+}
+
+/// Run a part, print its result and submit it if requested.
+/// A panic of the part is caught and reported as a failure, so that the other part still runs.
+pub fn run_part<'a, I: Clone, O: PartOutput<'a>>(
+    func: impl Fn(I) -> O,
+    input: I,
+    year: Year,
+    day: Day,
+    part: u8,
+) -> PartStatus {
     let part_str = format!("Part {part}");
+    let is_json = is_json_output();
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, report) = measure_part(func, input, part, bench_config().as_ref(), |result| {
+        if !is_json {
+            print_result(result, &part_str, "", None);
+        }
+    });
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    if is_json {
+        println!("{}", report.to_json_line());
+    } else {
+        print_result(
+            &result,
+            &part_str,
+            &format_duration(&report.duration(), report.samples),
+            report.memory.as_ref(),
+        );
+        if let Some(stats) = &report.stats {
+            print_stats(stats);
+        }
+    }
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    if let Outcome::Solved(result) = result {
+        submit_result(result, year, day, part);
     }
+
+    report.status
 }
 
-/// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
-///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Clone, T>(
-    func: impl Fn(I) -> T,
+/// Run a [`Solution`] in-process, passing every report to `on_report`.
+pub fn report_parsed<S: Solution>(
+    input: &str,
+    bench: Option<&BenchConfig>,
+    on_report: &mut dyn FnMut(Report),
+) {
+    let (parsed, report) = measure_parse::<S>(input, bench);
+    on_report(Report::Parse(report));
+    on_report(Report::Part(report_part(S::part_one, &parsed, 1, bench)));
+    on_report(Report::Part(report_part(S::part_two, &parsed, 2, bench)));
+}
+
+/// Run a solution part in-process and return its report.
+/// Nothing is printed, so that reports of days running in parallel can be buffered by the caller.
+pub fn report_part<'a, I: Clone, O: PartOutput<'a>>(
+    func: impl Fn(I) -> O,
+    input: I,
+    part: u8,
+    bench: Option<&BenchConfig>,
+) -> PartReport {
+    measure_part(func, input, part, bench, |_| {}).1
+}
+
+fn measure_parse<S: Solution>(
+    input: &str,
+    bench: Option<&BenchConfig>,
+) -> (S::Parsed, ParseReport) {
+    let (parsed, duration, samples, stats, _) =
+        run_timed(S::parse, S::parse, input, |_| {}, |_| true, bench);
+
+    let report = ParseReport {
+        #[allow(clippy::cast_precision_loss)]
+        nanos: duration.as_nanos() as f64,
+        samples,
+        stats,
+    };
+
+    (parsed, report)
+}
+
+fn measure_part<'a, I: Clone, O: PartOutput<'a>>(
+    func: impl Fn(I) -> O,
+    input: I,
+    part: u8,
+    bench: Option<&BenchConfig>,
+    hook: impl Fn(&Outcome<O::Answer>),
+) -> (Outcome<O::Answer>, PartReport) {
+    // only the first run is guarded, so that benched samples don't include the panic handling.
+    let first = |input| match catch_panic(|| func(input)) {
+        Ok(output) => output.into_outcome(),
+        Err(panic) => Outcome::Failed(vec![panic]),
+    };
+    // a failed part is not benched, a panic would only repeat itself.
+    let can_bench = |result: &Outcome<O::Answer>| !matches!(result, Outcome::Failed(_));
+    let (result, duration, samples, stats, memory) =
+        run_timed(first, &func, input, hook, can_bench, bench);
+
+    let (answer, status, error) = match &result {
+        Outcome::Solved(answer) => (Some(answer.to_string()), PartStatus::Solved, vec![]),
+        Outcome::Unsolved => (None, PartStatus::Unsolved, vec![]),
+        Outcome::Failed(error) => (None, PartStatus::Failed, error.clone()),
+    };
+
+    let report = PartReport {
+        part,
+        answer,
+        #[allow(clippy::cast_precision_loss)]
+        nanos: duration.as_nanos() as f64,
+        samples,
+        status,
+        stats,
+        memory,
+        error,
+    };
+
+    (result, report)
+}
+
+/// Solution binaries are benched when the `--time` flag is passed.
+fn bench_config() -> Option<BenchConfig> {
+    env::args()
+        .any(|x| x == "--time")
+        .then(BenchConfig::from_env)
+}
+
+/// Run a solution part. The behavior differs depending on whether a bench configuration is passed:
+///  1. without one, the function is executed once.
+///  2. with one, the function is benched and the median of all samples is reported.
+///     See [`BenchConfig`] for how long it is benched.
+///
+/// The result is taken from `first`, while `func` is the function that gets benched.
+/// Results for which `can_bench` returns false are reported without benching.
+///
+/// The heap usage of the first execution is reported when built with the `dhat-heap` feature.
+fn run_timed<I: Clone, T, U>(
+    first: impl FnOnce(I) -> T,
+    func: impl Fn(I) -> U,
     input: I,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+    can_bench: impl Fn(&T) -> bool,
+    bench_config: Option<&BenchConfig>,
+) -> (T, Duration, u128, Option<BenchStats>, Option<HeapUsage>) {
+    Progress::Started.report();
+
     let timer = Instant::now();
-    let result = {
+    let (result, memory) = {
         let input = input.clone();
-
-        #[cfg(feature = "dhat-heap")]
-        let _profiler = dhat::Profiler::new_heap();
-
-        func(input)
+        let profiler = HeapProfiler::start();
+        let result = first(input);
+        (result, profiler.finish())
     };
     let base_time = timer.elapsed();
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+    if let Some(config) = bench_config.filter(|_| can_bench(&result)) {
+        let stats = bench(func, input, &base_time, config);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let median = Duration::from_nanos(stats.median as u64);
+        (result, median, stats.samples, Some(stats), memory)
     } else {
-        (base_time, 1)
-    };
-
-    (result, run.0, run.1)
+        (result, base_time, 1, None, memory)
+    }
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+fn bench<I: Clone, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    base_time: &Duration,
+    config: &BenchConfig,
+) -> BenchStats {
     let mut stdout = stdout();
 
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+    if !is_json_output() {
+        print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
+        let _ = stdout.flush();
+    }
 
-    let bench_iterations =
-        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
+    let bench_iterations = config.sample_count(base_time);
+
+    let runs = u128::from(config.warmup) + bench_iterations;
+    Progress::Benching {
+        runs: u32::try_from(runs).unwrap_or(u32::MAX),
+    }
+    .report();
+
+    for _ in 0..config.warmup {
+        black_box(func(black_box(input.clone())));
+    }
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -71,22 +351,18 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         timers.push(timer.elapsed());
     }
 
-    (
-        #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+    BenchStats::from_samples(&timers).expect("at least one sample is taken")
 }
 
-fn average_duration(numbers: &[Duration]) -> u128 {
-    numbers
-        .iter()
-        .map(std::time::Duration::as_nanos)
-        .sum::<u128>()
-        / numbers.len() as u128
+pub(crate) fn print_stats(stats: &BenchStats) {
+    println!("{}", format_stats_line(stats));
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
+pub(crate) fn format_stats_line(stats: &BenchStats) -> String {
+    format!("  {ANSI_ITALIC}{}{ANSI_RESET}", format_stats(stats))
+}
+
+pub(crate) fn format_duration(duration: &Duration, samples: u128) -> String {
     if samples == 1 {
         format!(" ({duration:.1?})")
     } else {
@@ -94,53 +370,78 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+pub(crate) fn print_result<T: Display>(
+    result: &Outcome<T>,
+    part: &str,
+    duration_str: &str,
+    memory: Option<&HeapUsage>,
+) {
     let is_intermediate_result = duration_str.is_empty();
 
+    if !is_intermediate_result {
+        print!("\r{}", format_result(result, part, duration_str, memory));
+        return;
+    }
+
     match result {
-        Some(result) => {
+        Outcome::Solved(result) => {
             if result.to_string().contains('\n') {
-                let str = format!("{part}: ▼ {duration_str}");
-                if is_intermediate_result {
-                    print!("{str}");
-                } else {
-                    print!("\r");
-                    println!("{str}");
-                    println!("{result}");
-                }
+                print!("{part}: ▼ ");
             } else {
-                let str = format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
-                if is_intermediate_result {
-                    print!("{str}");
-                } else {
-                    print!("\r");
-                    println!("{str}");
-                }
+                print!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}");
             }
         }
-        None => {
-            if is_intermediate_result {
-                print!("{part}: ✖");
+        Outcome::Unsolved => {
+            print!("{part}: ✖");
+        }
+        Outcome::Failed(_) => {
+            print!("{part}: ✖ failed");
+        }
+    }
+}
+
+/// Formats the final result of a part, including a trailing newline.
+/// The heap usage of the part follows its execution time, if it was measured.
+/// The error of a failed part is listed below it, one line per source.
+pub(crate) fn format_result<T: Display>(
+    result: &Outcome<T>,
+    part: &str,
+    duration_str: &str,
+    memory: Option<&HeapUsage>,
+) -> String {
+    let memory_str = memory.map_or_else(String::new, |memory| {
+        format!(" {ANSI_ITALIC}[{}]{ANSI_RESET}", format_usage(memory))
+    });
+    let duration_str = format!("{duration_str}{memory_str}");
+
+    match result {
+        Outcome::Solved(result) => {
+            if result.to_string().contains('\n') {
+                format!("{part}: ▼ {duration_str}\n{result}\n")
             } else {
-                print!("\r");
-                println!("{part}: ✖             ");
+                format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}\n")
             }
         }
+        Outcome::Unsolved => format!("{part}: ✖             \n"),
+        Outcome::Failed(error) => {
+            let mut lines = format!("{part}: ✖ {ANSI_BOLD}failed{ANSI_RESET}{duration_str}\n");
+            for (i, message) in error.iter().enumerate() {
+                let label = if i == 0 { "error" } else { "caused by" };
+                lines.push_str(&format!("  {ANSI_ITALIC}{label}:{ANSI_RESET} {message}\n"));
+            }
+            lines
+        }
     }
 }
 
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
-    day: Day,
-    part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+///  2. a session cookie is configured.
+fn submit_result<T: Display>(result: T, year: Year, day: Day, part: u8) {
     let args: Vec<String> = env::args().collect();
 
     if !args.contains(&"--submit".into()) {
-        return None;
+        return;
     }
 
     if args.len() < 3 {
@@ -156,14 +457,153 @@ fn submit_result<T: Display>(
     };
 
     if part_submit != part {
-        return None;
+        return;
     }
 
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+    let answer = result.to_string();
+    let mut submissions = match Submissions::read_from_file(year) {
+        Ok(submissions) => submissions,
+        Err(e) => {
+            eprintln!("Refusing to submit {answer}: {e}");
+            process::exit(1);
+        }
+    };
+
+    if let Err(refusal) = submissions.check(day, part, &answer) {
+        eprintln!("Refusing to submit {answer}: {refusal}");
         process::exit(1);
     }
 
-    println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    let client = match AocClient::from_env(year) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Failed to submit result: {e}");
+            process::exit(1);
+        }
+    };
+
+    println!("Submitting result...");
+    let verdict = match client.submit(day, part, &answer) {
+        Ok(message) => {
+            println!("{message}");
+            Verdict::from_message(&message)
+        }
+        Err(AocCommandError::RateLimited(wait)) => Verdict::RateLimited(wait),
+        Err(e) => {
+            eprintln!("Failed to submit result: {e}");
+            process::exit(1);
+        }
+    };
+
+    println!("Verdict: {verdict}");
+
+    if verdict == Verdict::Correct {
+        match Answers::read_from_file(year) {
+            Ok(mut answers) => {
+                answers.set(day, part, &answer);
+                if let Err(e) = answers.store_file(year) {
+                    eprintln!("Failed to store accepted answer: {e}");
+                }
+            }
+            Err(e) => eprintln!("Failed to store accepted answer: {e}"),
+        }
+    }
+
+    submissions
+        .data
+        .push(Submission::new(day, part, &answer, verdict));
+
+    if let Err(e) = submissions.store_file(year) {
+        eprintln!("Failed to store submission: {e}");
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::fmt::Display;
+
+    use super::{catch_panic, format_result, report_part, Outcome, PartOutput};
+    use crate::template::protocol::PartStatus;
+    use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+    #[derive(Debug)]
+    struct InvalidLine(usize, std::num::ParseIntError);
+
+    impl Display for InvalidLine {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "invalid number on line {}", self.0)
+        }
+    }
+
+    impl std::error::Error for InvalidLine {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.1)
+        }
+    }
+
+    #[test]
+    fn converts_part_outputs() {
+        assert_eq!(Some(42).into_outcome(), Outcome::Solved(42));
+        assert_eq!(None::<u32>.into_outcome(), Outcome::Unsolved);
+        assert_eq!(Ok::<_, String>(42).into_outcome(), Outcome::Solved(42));
+        assert_eq!(
+            Err::<u32, _>("no path found").into_outcome(),
+            Outcome::Failed(vec!["no path found".into()])
+        );
+    }
+
+    #[test]
+    fn collects_error_chain() {
+        let source = "x".parse::<u32>().unwrap_err();
+        let result: Result<u32, _> = Err(InvalidLine(3, source));
+
+        assert_eq!(
+            result.into_outcome(),
+            Outcome::Failed(vec![
+                "invalid number on line 3".into(),
+                "invalid digit found in string".into()
+            ])
+        );
+    }
+
+    #[test]
+    fn formats_failed_parts() {
+        let result: Outcome<u32> = Outcome::Failed(vec![
+            "invalid number on line 3".into(),
+            "invalid digit".into(),
+        ]);
+
+        assert_eq!(
+            format_result(&result, "Part 1", " (1.0µs)", None),
+            format!(
+                "Part 1: ✖ {ANSI_BOLD}failed{ANSI_RESET} (1.0µs)\n  {ANSI_ITALIC}error:{ANSI_RESET} invalid number on line 3\n  {ANSI_ITALIC}caused by:{ANSI_RESET} invalid digit\n"
+            )
+        );
+    }
+
+    #[test]
+    fn catches_panics() {
+        assert_eq!(catch_panic(|| 42), Ok(42));
+
+        let line = line!() + 1;
+        let error = catch_panic(|| -> u32 { panic!("no path to {}", 7) }).unwrap_err();
+        let location = format!("panicked at {}:{line}:", file!());
+        assert!(error.starts_with(&location), "{error}");
+        assert!(error.ends_with(": no path to 7"), "{error}");
+    }
+
+    #[test]
+    fn reports_panicking_parts_as_failed() {
+        let report = report_part(|_: &str| -> Option<u32> { panic!("oops") }, "", 1, None);
+        assert_eq!(report.status, PartStatus::Failed);
+        assert_eq!(report.answer, None);
+        assert!(report.error[0].ends_with(": oops"), "{:?}", report.error);
+
+        // catching a panic does not affect the next part.
+        let report = report_part(|_: &str| Some(2), "", 2, None);
+        assert_eq!(report.status, PartStatus::Solved);
+        assert_eq!(report.answer.as_deref(), Some("2"));
+    }
 }
@@ -0,0 +1,39 @@
+use std::fmt::Display;
+
+/// A solution that parses its input once and shares the parsed value between both parts.
+/// Parse time and the time of each part are reported separately.
+///
+/// Register an implementation with `advent_of_code::solution!(<day>, <type>)`.
+///
+/// ```ignore
+/// advent_of_code::solution!(1, Day01);
+///
+/// pub struct Day01;
+///
+/// impl advent_of_code::template::Solution for Day01 {
+///     type Parsed = Vec<u32>;
+///     type Answer1 = u32;
+///     type Answer2 = u32;
+///
+///     fn parse(input: &str) -> Self::Parsed {
+///         input.lines().map(|l| l.parse().unwrap()).collect()
+///     }
+///
+///     fn part_one(parsed: &Self::Parsed) -> Option<Self::Answer1> {
+///         parsed.iter().max().copied()
+///     }
+///
+///     fn part_two(parsed: &Self::Parsed) -> Option<Self::Answer2> {
+///         Some(parsed.iter().sum())
+///     }
+/// }
+/// ```
+pub trait Solution {
+    type Parsed;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Self::Parsed;
+    fn part_one(parsed: &Self::Parsed) -> Option<Self::Answer1>;
+    fn part_two(parsed: &Self::Parsed) -> Option<Self::Answer2>;
+}
@@ -0,0 +1,484 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    io::{Error, ErrorKind},
+    path::Path,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tinyjson::JsonValue;
+
+use crate::template::{data_dir, Day, Year};
+
+/// Name of the file inside the data directory of a year.
+static SUBMISSIONS_FILE_NAME: &str = "submissions.json";
+
+/// The verdict the server gave for a submitted answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    RateLimited(Option<Duration>),
+    AlreadySolved,
+    Unknown,
+}
+
+impl Verdict {
+    /// Parses the message returned by the server after submitting an answer.
+    pub fn from_message(message: &str) -> Self {
+        if message.contains("That's the right answer") {
+            Verdict::Correct
+        } else if message.contains("That's not the right answer") {
+            if message.contains("too high") {
+                Verdict::TooHigh
+            } else if message.contains("too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            }
+        } else if message.contains("You don't seem to be solving the right level") {
+            Verdict::AlreadySolved
+        } else {
+            Verdict::Unknown
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::Wrong => "wrong",
+            Verdict::TooHigh => "too_high",
+            Verdict::TooLow => "too_low",
+            Verdict::RateLimited(_) => "rate_limited",
+            Verdict::AlreadySolved => "already_solved",
+            Verdict::Unknown => "unknown",
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+            Verdict::RateLimited(Some(wait)) => {
+                write!(f, "rate limited ({}s left to wait)", wait.as_secs())
+            }
+            Verdict::RateLimited(None) => write!(f, "rate limited"),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// A single answer that was sent to the server.
+#[derive(Clone, Debug)]
+pub struct Submission {
+    pub day: Day,
+    pub part: u8,
+    pub answer: String,
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub verdict: Verdict,
+}
+
+impl Submission {
+    pub fn new(day: Day, part: u8, answer: &str, verdict: Verdict) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        Self {
+            day,
+            part,
+            answer: answer.to_string(),
+            timestamp,
+            verdict,
+        }
+    }
+}
+
+/// Reasons for refusing to submit an answer.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Refusal {
+    KnownWrong,
+    NotBelow(String),
+    NotAbove(String),
+}
+
+impl Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Refusal::KnownWrong => write!(f, "this answer was already submitted and is wrong."),
+            Refusal::NotBelow(bound) => {
+                write!(
+                    f,
+                    "the answer must be lower than {bound}, which was too high."
+                )
+            }
+            Refusal::NotAbove(bound) => {
+                write!(
+                    f,
+                    "the answer must be higher than {bound}, which was too low."
+                )
+            }
+        }
+    }
+}
+
+/// Ledger of every answer submitted so far.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Submissions {
+    pub data: Vec<Submission>,
+}
+
+impl Submissions {
+    /// Dehydrate submissions to the JSON file of a year.
+    pub fn store_file(&self, year: Year) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        fs::create_dir_all(data_dir(year))?;
+        let mut file = fs::File::create(data_dir(year).join(SUBMISSIONS_FILE_NAME))?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate submissions from the JSON file of a year. If not present, returns an empty ledger.
+    /// A file that can't be read or parsed is an error, so that it is never overwritten by an empty ledger.
+    pub fn read_from_file(year: Year) -> Result<Self, String> {
+        Self::read_from_path(&data_dir(year).join(SUBMISSIONS_FILE_NAME))
+    }
+
+    fn read_from_path(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(json) => Submissions::try_from(json),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.to_string()),
+        }
+        .map_err(|e| format!("could not read {}: {e}", path.display()))
+    }
+
+    /// Checks an answer against earlier verdicts for the same day and part.
+    pub fn check(&self, day: Day, part: u8, answer: &str) -> Result<(), Refusal> {
+        let previous = self.data.iter().filter(|s| s.day == day && s.part == part);
+
+        let numeric_answer = answer.trim().parse::<i128>().ok();
+
+        for submission in previous {
+            match submission.verdict {
+                Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow
+                    if submission.answer == answer =>
+                {
+                    return Err(Refusal::KnownWrong);
+                }
+                _ => {}
+            }
+
+            let (Some(answer), Ok(bound)) = (numeric_answer, submission.answer.parse::<i128>())
+            else {
+                continue;
+            };
+
+            match submission.verdict {
+                Verdict::TooHigh if answer >= bound => {
+                    return Err(Refusal::NotBelow(submission.answer.clone()));
+                }
+                Verdict::TooLow if answer <= bound => {
+                    return Err(Refusal::NotAbove(submission.answer.clone()));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Submissions> for JsonValue {
+    fn from(value: Submissions) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Submissions {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Submissions {
+            data: json_data
+                .iter()
+                .map(Submission::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Submission> for JsonValue {
+    fn from(value: &Submission) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert("answer".into(), JsonValue::String(value.answer.clone()));
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(value.timestamp as f64),
+        );
+        map.insert(
+            "verdict".into(),
+            JsonValue::String(value.verdict.as_str().into()),
+        );
+
+        map.insert(
+            "wait_secs".into(),
+            match value.verdict {
+                #[allow(clippy::cast_precision_loss)]
+                Verdict::RateLimited(Some(wait)) => JsonValue::Number(wait.as_secs() as f64),
+                _ => JsonValue::Null,
+            },
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Submission {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected submission to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected submission.day to be a Day struct.")?;
+
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .map(|v| *v as u8)
+            .ok_or("Expected submission.part to be a number.")?;
+
+        let answer = json
+            .get("answer")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected submission.answer to be a string.")?;
+
+        let timestamp = json
+            .get("timestamp")
+            .and_then(|v| v.get::<f64>())
+            .map(|v| *v as u64)
+            .ok_or("Expected submission.timestamp to be a number.")?;
+
+        let wait = json
+            .get("wait_secs")
+            .and_then(|v| v.get::<f64>())
+            .map(|v| Duration::from_secs(*v as u64));
+
+        let verdict = match json.get("verdict").and_then(|v| v.get::<String>()) {
+            Some(s) if s == "correct" => Verdict::Correct,
+            Some(s) if s == "wrong" => Verdict::Wrong,
+            Some(s) if s == "too_high" => Verdict::TooHigh,
+            Some(s) if s == "too_low" => Verdict::TooLow,
+            Some(s) if s == "rate_limited" => Verdict::RateLimited(wait),
+            Some(s) if s == "already_solved" => Verdict::AlreadySolved,
+            Some(s) if s == "unknown" => Verdict::Unknown,
+            _ => return Err("Expected submission.verdict to be a known verdict.".into()),
+        };
+
+        Ok(Submission {
+            day,
+            part,
+            answer: answer.clone(),
+            timestamp,
+            verdict,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use crate::day;
+
+    use super::{Refusal, Submission, Submissions, Verdict};
+
+    fn submission(day: u8, part: u8, answer: &str, verdict: Verdict) -> Submission {
+        Submission {
+            day: crate::template::Day::new(day).unwrap(),
+            part,
+            answer: answer.into(),
+            timestamp: 1_733_000_000,
+            verdict,
+        }
+    }
+
+    mod verdicts {
+        use super::Verdict;
+
+        #[test]
+        fn parses_server_messages() {
+            assert_eq!(
+                Verdict::from_message("That's the right answer! You are one gold star closer."),
+                Verdict::Correct
+            );
+            assert_eq!(
+                Verdict::from_message("That's not the right answer; your answer is too high."),
+                Verdict::TooHigh
+            );
+            assert_eq!(
+                Verdict::from_message("That's not the right answer; your answer is too low."),
+                Verdict::TooLow
+            );
+            assert_eq!(
+                Verdict::from_message("That's not the right answer. If you're stuck, ..."),
+                Verdict::Wrong
+            );
+            assert_eq!(
+                Verdict::from_message(
+                    "You don't seem to be solving the right level. Did you already complete it?"
+                ),
+                Verdict::AlreadySolved
+            );
+            assert_eq!(Verdict::from_message("Huh?"), Verdict::Unknown);
+        }
+    }
+
+    mod check {
+        use super::{day, submission, Refusal, Submissions, Verdict};
+
+        #[test]
+        fn refuses_known_wrong_answers() {
+            let submissions = Submissions {
+                data: vec![submission(1, 1, "abc", Verdict::Wrong)],
+            };
+            assert_eq!(
+                submissions.check(day!(1), 1, "abc"),
+                Err(Refusal::KnownWrong)
+            );
+            assert_eq!(submissions.check(day!(1), 1, "abd"), Ok(()));
+            assert_eq!(submissions.check(day!(1), 2, "abc"), Ok(()));
+            assert_eq!(submissions.check(day!(2), 1, "abc"), Ok(()));
+        }
+
+        #[test]
+        fn refuses_answers_outside_bounds() {
+            let submissions = Submissions {
+                data: vec![
+                    submission(1, 1, "100", Verdict::TooHigh),
+                    submission(1, 1, "10", Verdict::TooLow),
+                ],
+            };
+            assert_eq!(
+                submissions.check(day!(1), 1, "100"),
+                Err(Refusal::KnownWrong)
+            );
+            assert_eq!(
+                submissions.check(day!(1), 1, "150"),
+                Err(Refusal::NotBelow("100".into()))
+            );
+            assert_eq!(
+                submissions.check(day!(1), 1, "5"),
+                Err(Refusal::NotAbove("10".into()))
+            );
+            assert_eq!(submissions.check(day!(1), 1, "50"), Ok(()));
+        }
+
+        #[test]
+        fn allows_answers_after_rate_limits() {
+            let submissions = Submissions {
+                data: vec![submission(1, 1, "42", Verdict::RateLimited(None))],
+            };
+            assert_eq!(submissions.check(day!(1), 1, "42"), Ok(()));
+        }
+    }
+
+    mod serialization {
+        use std::{env, fs, time::Duration};
+        use tinyjson::JsonValue;
+
+        use super::{submission, Submissions, Verdict};
+
+        #[test]
+        fn round_trips_submissions() {
+            let submissions = Submissions {
+                data: vec![
+                    submission(1, 1, "42", Verdict::TooLow),
+                    submission(
+                        1,
+                        1,
+                        "43",
+                        Verdict::RateLimited(Some(Duration::from_secs(37))),
+                    ),
+                    submission(1, 2, "44", Verdict::Correct),
+                ],
+            };
+
+            let json = JsonValue::from(submissions).stringify().unwrap();
+            let parsed = Submissions::try_from(json).unwrap();
+
+            assert_eq!(parsed.data.len(), 3);
+            assert_eq!(parsed.data[0].answer, "42");
+            assert_eq!(parsed.data[0].verdict, Verdict::TooLow);
+            assert_eq!(parsed.data[0].timestamp, 1_733_000_000);
+            assert_eq!(
+                parsed.data[1].verdict,
+                Verdict::RateLimited(Some(Duration::from_secs(37)))
+            );
+            assert_eq!(parsed.data[2].part, 2);
+        }
+
+        #[test]
+        #[should_panic]
+        fn panics_for_unknown_verdicts() {
+            let json = r#"{ "data": [{ "day": "01", "part": 1, "answer": "1", "timestamp": 0, "verdict": "maybe", "wait_secs": null }] }"#.to_string();
+            Submissions::try_from(json).unwrap();
+        }
+
+        #[test]
+        fn only_treats_missing_files_as_empty() {
+            let dir = env::temp_dir().join(format!("aoc-submissions-{}", std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            let path = dir.join("submissions.json");
+
+            let missing = Submissions::read_from_path(&path);
+            fs::write(&path, "{ \"data\": [{ \"day\": \"01\" }] }").unwrap();
+            let malformed = Submissions::read_from_path(&path);
+            fs::remove_dir_all(&dir).unwrap();
+
+            assert!(missing.unwrap().data.is_empty());
+            assert!(malformed.unwrap_err().starts_with("could not read"));
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use std::{collections::HashMap, fs, io::Error, str::FromStr};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
+use crate::template::bench::{parse_duration, stats_from_json, stats_to_json, BenchStats};
+use crate::template::memory::{usage_from_json, usage_to_json, HeapUsage};
+use crate::template::{data_dir, Calendar, Day, Year};
 
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+/// Name of the file inside the data directory of a year.
+static TIMINGS_FILE_NAME: &str = "timings.json";
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
@@ -12,6 +15,61 @@ pub struct Timing {
     pub part_1: Option<String>,
     pub part_2: Option<String>,
     pub total_nanos: f64,
+    pub part_1_stats: Option<BenchStats>,
+    pub part_2_stats: Option<BenchStats>,
+    /// Time spent parsing the input, for solutions implementing [`Solution`](crate::template::Solution).
+    pub parse: Option<String>,
+    pub parse_stats: Option<BenchStats>,
+    /// Parts that were cancelled after exceeding a timeout.
+    pub timed_out: Vec<u8>,
+    /// Parts that returned an error.
+    pub failed: Vec<u8>,
+    /// Heap usage of the parts, recorded by `cargo time --memory`.
+    pub part_1_memory: Option<HeapUsage>,
+    pub part_2_memory: Option<HeapUsage>,
+}
+
+impl Timing {
+    /// A timing of `day` without any measured parts.
+    pub fn new(day: Day) -> Self {
+        Self {
+            day,
+            part_1: None,
+            part_2: None,
+            total_nanos: 0_f64,
+            part_1_stats: None,
+            part_2_stats: None,
+            parse: None,
+            parse_stats: None,
+            timed_out: vec![],
+            failed: vec![],
+            part_1_memory: None,
+            part_2_memory: None,
+        }
+    }
+
+    /// Time of a part in nanoseconds, if it was solved.
+    pub fn part_nanos(&self, part: u8) -> Option<f64> {
+        match part {
+            1 => to_nanos(self.part_1.as_deref(), self.part_1_stats.as_ref()),
+            2 => to_nanos(self.part_2.as_deref(), self.part_2_stats.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Time spent parsing the input in nanoseconds, if reported separately.
+    pub fn parse_nanos(&self) -> Option<f64> {
+        to_nanos(self.parse.as_deref(), self.parse_stats.as_ref())
+    }
+}
+
+/// Prefers the exact median of the stats over the rounded display string.
+fn to_nanos(display: Option<&str>, stats: Option<&BenchStats>) -> Option<f64> {
+    let display = display?;
+    stats.map(|s| s.median).or_else(|| {
+        #[allow(clippy::cast_precision_loss)]
+        parse_duration(display).map(|d| d.as_nanos() as f64)
+    })
 }
 
 /// Represents benchmark times for a set of days.
@@ -22,27 +80,34 @@ pub struct Timings {
 }
 
 impl Timings {
-    /// Dehydrate timings to a JSON file.
-    pub fn store_file(&self) -> Result<(), Error> {
+    /// Dehydrate timings to the JSON file of a year.
+    pub fn store_file(&self, year: Year) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        fs::create_dir_all(data_dir(year))?;
+        let mut file = fs::File::create(data_dir(year).join(TIMINGS_FILE_NAME))?;
         json.format_to(&mut file)
     }
 
-    /// Rehydrate timings from a JSON file. If not present, returns empty timings.
-    pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
+    /// Rehydrate timings from the JSON file of a year. If not present, returns empty timings.
+    pub fn read_from_file(year: Year) -> Self {
+        fs::read_to_string(data_dir(year).join(TIMINGS_FILE_NAME))
             .map_err(|x| x.to_string())
             .and_then(Timings::try_from)
             .unwrap_or_default()
     }
 
     /// Merge two sets of timings, overwriting `self` with `other` if present.
+    /// Heap usage is only recorded with `--memory`, so a day keeps its old usage unless `other` has a new one.
     pub fn merge(&self, new: &Self) -> Self {
         let mut data: Vec<Timing> = vec![];
 
         for timing in &new.data {
-            data.push(timing.clone());
+            let mut timing = timing.clone();
+            if let Some(old) = self.data.iter().find(|t| t.day == timing.day) {
+                timing.part_1_memory = timing.part_1_memory.or(old.part_1_memory);
+                timing.part_2_memory = timing.part_2_memory.or(old.part_2_memory);
+            }
+            data.push(timing);
         }
 
         for timing in &self.data {
@@ -51,7 +116,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.day);
         Timings { data }
     }
 
@@ -60,10 +125,12 @@ impl Timings {
         self.data.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64
     }
 
-    pub fn is_day_complete(&self, day: Day) -> bool {
+    /// Whether every part the event has on this day was timed.
+    pub fn is_day_complete(&self, year: Year, day: Day) -> bool {
+        let has_part_two = Calendar::of(year).has_part_two(day);
         self.data
             .iter()
-            .any(|t| t.day == day && t.part_1.is_some() && t.part_2.is_some())
+            .any(|t| t.day == day && t.part_1.is_some() && (t.part_2.is_some() || !has_part_two))
     }
 }
 
@@ -133,6 +200,52 @@ impl From<&Timing> for JsonValue {
             },
         );
 
+        map.insert(
+            "part_1_stats".into(),
+            stats_to_json(value.part_1_stats.as_ref()),
+        );
+        map.insert(
+            "part_2_stats".into(),
+            stats_to_json(value.part_2_stats.as_ref()),
+        );
+
+        map.insert(
+            "parse".into(),
+            value
+                .parse
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+        map.insert(
+            "parse_stats".into(),
+            stats_to_json(value.parse_stats.as_ref()),
+        );
+
+        if value.part_1_memory.is_some() || value.part_2_memory.is_some() {
+            map.insert(
+                "part_1_memory".into(),
+                usage_to_json(value.part_1_memory.as_ref()),
+            );
+            map.insert(
+                "part_2_memory".into(),
+                usage_to_json(value.part_2_memory.as_ref()),
+            );
+        }
+
+        for (key, parts) in [("timed_out", &value.timed_out), ("failed", &value.failed)] {
+            if !parts.is_empty() {
+                map.insert(
+                    key.into(),
+                    JsonValue::Array(
+                        parts
+                            .iter()
+                            .map(|part| JsonValue::Number(f64::from(*part)))
+                            .collect(),
+                    ),
+                );
+            }
+        }
+
         JsonValue::Object(map)
     }
 }
@@ -166,15 +279,56 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        // stats are optional to keep reading files written before they were recorded.
+        let part_1_stats = stats_from_json(json, "part_1_stats")?;
+        let part_2_stats = stats_from_json(json, "part_2_stats")?;
+
+        let parse = match json.get("parse") {
+            None | Some(JsonValue::Null) => None,
+            Some(JsonValue::String(s)) => Some(s.clone()),
+            Some(_) => return Err("Expected timing.parse to be null or string.".into()),
+        };
+        let parse_stats = stats_from_json(json, "parse_stats")?;
+
+        let timed_out = parts_from_json(json, "timed_out")?;
+        let failed = parts_from_json(json, "failed")?;
+
+        let part_1_memory = usage_from_json(json, "part_1_memory")?;
+        let part_2_memory = usage_from_json(json, "part_2_memory")?;
+
         Ok(Timing {
             day,
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
             total_nanos,
+            part_1_stats,
+            part_2_stats,
+            parse,
+            parse_stats,
+            timed_out,
+            failed,
+            part_1_memory,
+            part_2_memory,
         })
     }
 }
 
+/// Reads an optional list of part numbers stored under `key` of a JSON object.
+fn parts_from_json(json: &HashMap<String, JsonValue>, key: &str) -> Result<Vec<u8>, String> {
+    match json.get(key) {
+        None | Some(JsonValue::Null) => Ok(vec![]),
+        Some(JsonValue::Array(parts)) => parts
+            .iter()
+            .map(|part| match part {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                JsonValue::Number(n) => Ok(*n as u8),
+                _ => Err(format!("Expected timing.{key} to contain part numbers.")),
+            })
+            .collect(),
+        Some(_) => Err(format!("Expected timing.{key} to be an array.")),
+    }
+}
+
 /* -------------------------------------------------------------------------- */
 
 #[cfg(feature = "test_lib")]
@@ -187,22 +341,21 @@ mod tests {
         Timings {
             data: vec![
                 Timing {
-                    day: day!(1),
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    ..Timing::new(day!(1))
                 },
                 Timing {
-                    day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    ..Timing::new(day!(2))
                 },
                 Timing {
-                    day: day!(4),
                     part_1: Some("40ms".into()),
-                    part_2: None,
                     total_nanos: 4e+10,
+                    ..Timing::new(day!(4))
                 },
             ],
         }
@@ -223,6 +376,60 @@ mod tests {
             assert_eq!(timing.total_nanos, 1_000_000_000_f64);
         }
 
+        #[test]
+        fn handles_json_stats() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000, "part_1_stats": { "samples": 10, "min": 900000, "median": 1000000, "mean": 1100000, "p95": 1500000, "max": 2000000, "std_dev": 100000, "outliers": 1 }, "part_2_stats": null }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            let stats = timing.part_1_stats.as_ref().unwrap();
+            assert_eq!(stats.samples, 10);
+            assert_eq!(stats.median, 1_000_000_f64);
+            assert_eq!(stats.outliers, 1);
+            assert!(timing.part_2_stats.is_none());
+        }
+
+        #[test]
+        fn handles_parse_timings() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": "2ms", "total_nanos": 3500000, "parse": "500.0µs" }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.parse, Some("500.0µs".to_string()));
+            assert!(timing.parse_stats.is_none());
+
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": "2ms", "total_nanos": 3000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            assert_eq!(timings.data.first().unwrap().parse, None);
+        }
+
+        #[test]
+        fn handles_timed_out_parts() {
+            let json = r#"{ "data": [{ "day": "06", "part_1": "1ms", "part_2": null, "total_nanos": 1000000, "timed_out": [2] }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.timed_out, vec![2]);
+
+            let json = tinyjson::JsonValue::from(timings.clone())
+                .stringify()
+                .unwrap();
+            let roundtrip = Timings::try_from(json).unwrap();
+            assert_eq!(roundtrip.data.first().unwrap().timed_out, vec![2]);
+        }
+
+        #[test]
+        fn handles_failed_parts() {
+            let json = r#"{ "data": [{ "day": "05", "part_1": null, "part_2": "1ms", "total_nanos": 1000000, "failed": [1] }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.failed, vec![1]);
+            assert!(timing.timed_out.is_empty());
+
+            let json = tinyjson::JsonValue::from(timings.clone())
+                .stringify()
+                .unwrap();
+            let roundtrip = Timings::try_from(json).unwrap();
+            assert_eq!(roundtrip.data.first().unwrap().failed, vec![1]);
+        }
+
         #[test]
         fn handles_empty_timings() {
             let json = r#"{ "data": [] }"#.to_string();
@@ -272,54 +479,96 @@ mod tests {
         use crate::{
             day,
             template::timings::{Timing, Timings},
+            year,
         };
 
         #[test]
         fn handles_completed_days() {
             let timings = Timings {
                 data: vec![Timing {
-                    day: day!(1),
                     part_1: Some("1ms".into()),
                     part_2: Some("2ms".into()),
                     total_nanos: 3_000_000_000_f64,
+                    ..Timing::new(day!(1))
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(year!(2024), day!(1)), true);
         }
 
         #[test]
         fn handles_partial_days() {
             let timings = Timings {
                 data: vec![Timing {
-                    day: day!(1),
                     part_1: Some("1ms".into()),
-                    part_2: None,
                     total_nanos: 1_000_000_000_f64,
+                    ..Timing::new(day!(1))
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(year!(2024), day!(1)), false);
         }
 
         #[test]
         fn handles_uncompleted_days() {
+            let timings = Timings {
+                data: vec![Timing::new(day!(1))],
+            };
+
+            assert_eq!(timings.is_day_complete(year!(2024), day!(1)), false);
+        }
+
+        #[test]
+        fn handles_last_days_without_part_two() {
             let timings = Timings {
                 data: vec![Timing {
-                    day: day!(1),
-                    part_1: None,
-                    part_2: None,
-                    total_nanos: 0.0,
+                    part_1: Some("1ms".into()),
+                    total_nanos: 1_000_000_000_f64,
+                    ..Timing::new(day!(12))
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(year!(2025), day!(12)), true);
+            assert_eq!(timings.is_day_complete(year!(2024), day!(12)), false);
+        }
+    }
+
+    mod nanos {
+        use crate::{
+            day,
+            template::{bench::BenchStats, timings::Timing},
+        };
+
+        #[test]
+        fn reads_part_times() {
+            let timing = Timing {
+                part_1: Some("1.5ms".into()),
+                part_2: Some("20.0µs".into()),
+                total_nanos: 1_520_000_f64,
+                part_2_stats: Some(BenchStats {
+                    samples: 10,
+                    min: 19_000.0,
+                    median: 20_040.0,
+                    mean: 20_100.0,
+                    p95: 21_000.0,
+                    max: 22_000.0,
+                    std_dev: 500.0,
+                    outliers: 0,
+                }),
+                ..Timing::new(day!(1))
+            };
+
+            assert_eq!(timing.part_nanos(1), Some(1_500_000_f64));
+            assert_eq!(timing.part_nanos(2), Some(20_040_f64));
+            assert_eq!(timing.part_nanos(3), None);
+            assert_eq!(timing.parse_nanos(), None);
         }
     }
 
     mod merge {
         use crate::{
             day,
+            template::memory::HeapUsage,
             template::timings::{Timing, Timings},
         };
 
@@ -329,12 +578,7 @@ mod tests {
         fn handles_disjunct_timings() {
             let timings = get_mock_timings();
             let other = Timings {
-                data: vec![Timing {
-                    day: day!(3),
-                    part_1: None,
-                    part_2: None,
-                    total_nanos: 0_f64,
-                }],
+                data: vec![Timing::new(day!(3))],
             };
             let merged = timings.merge(&other);
             assert_eq!(merged.data.len(), 4);
@@ -349,12 +593,7 @@ mod tests {
             let timings = get_mock_timings();
 
             let other = Timings {
-                data: vec![Timing {
-                    day: day!(2),
-                    part_1: None,
-                    part_2: None,
-                    total_nanos: 0_f64,
-                }],
+                data: vec![Timing::new(day!(2))],
             };
             let merged = timings.merge(&other);
 
@@ -365,6 +604,29 @@ mod tests {
             assert_eq!(merged.data[2].day, day!(4));
         }
 
+        #[test]
+        fn keeps_memory_without_new_usage() {
+            let usage = |peak_bytes| HeapUsage {
+                peak_bytes,
+                total_allocs: 1,
+                total_bytes: peak_bytes,
+            };
+
+            let mut timings = get_mock_timings();
+            timings.data[0].part_1_memory = Some(usage(100));
+            timings.data[0].part_2_memory = Some(usage(200));
+
+            let mut other = get_mock_timings();
+            other.data.truncate(1);
+            other.data[0].part_1 = Some("5ms".into());
+            other.data[0].part_2_memory = Some(usage(300));
+
+            let merged = timings.merge(&other);
+            assert_eq!(merged.data[0].part_1, Some("5ms".into()));
+            assert_eq!(merged.data[0].part_1_memory, Some(usage(100)));
+            assert_eq!(merged.data[0].part_2_memory, Some(usage(300)));
+        }
+
         #[test]
         fn handles_empty_timings() {
             let timings = Timings::default();
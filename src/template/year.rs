@@ -0,0 +1,179 @@
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[cfg(feature = "today")]
+use chrono::{Datelike, FixedOffset, Utc};
+
+#[cfg(feature = "today")]
+use crate::template::calendar::SERVER_UTC_OFFSET;
+
+/// The environment variable holding the default year, set in `.cargo/config.toml`.
+const YEAR_ENV: &str = "AOC_YEAR";
+
+/// A valid year of advent of code (i.e. 2015 or later).
+///
+/// # Display
+/// This value displays as a four digit number.
+///
+/// ```
+/// # use advent_of_code::template::Year;
+/// let year = Year::new(2024).unwrap();
+/// assert_eq!(year.to_string(), "2024")
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Year(u16);
+
+impl Year {
+    /// Creates a [`Year`] from the provided value if it's a year advent of code took place in,
+    /// returns [`None`] otherwise.
+    pub fn new(year: u16) -> Option<Self> {
+        if !(2015..=9999).contains(&year) {
+            return None;
+        }
+        Some(Self(year))
+    }
+
+    // Not part of the public API
+    #[doc(hidden)]
+    pub const fn __new_unchecked(year: u16) -> Self {
+        Self(year)
+    }
+
+    /// Converts the [`Year`] into an [`u16`].
+    pub const fn into_inner(self) -> u16 {
+        self.0
+    }
+
+    /// Reads the default year from the `AOC_YEAR` environment variable.
+    pub fn from_env() -> Option<Self> {
+        env::var(YEAR_ENV).ok()?.trim().parse().ok()
+    }
+
+    /// Reads the year from the path of a solution file named `<year>_<day>.rs`.
+    /// Used by the [`solution!`](crate::solution) macro with `file!()`, fails to compile for other names.
+    pub const fn from_bin_path(path: &str) -> Self {
+        let bytes = path.as_bytes();
+
+        // find the start of the file name.
+        let mut start = bytes.len();
+        while start > 0 && bytes[start - 1] != b'/' && bytes[start - 1] != b'\\' {
+            start -= 1;
+        }
+
+        if bytes.len() < start + 5 || bytes[start + 4] != b'_' {
+            panic!("solution files must be named `<year>_<day>.rs`, e.g. `2024_01.rs`");
+        }
+
+        let mut year: u16 = 0;
+        let mut i = start;
+        while i < start + 4 {
+            let digit = bytes[i];
+            if !digit.is_ascii_digit() {
+                panic!("solution files must be named `<year>_<day>.rs`, e.g. `2024_01.rs`");
+            }
+            year = year * 10 + (digit - b'0') as u16;
+            i += 1;
+        }
+
+        if year < 2015 {
+            panic!("advent of code started in 2015");
+        }
+
+        Self(year)
+    }
+}
+
+#[cfg(feature = "today")]
+impl Year {
+    /// Returns the current year in the timezone of the advent of code server.
+    pub fn today() -> Option<Self> {
+        let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
+        let today = Utc::now().with_timezone(&offset);
+        Self::new(u16::try_from(today.year()).ok()?)
+    }
+}
+
+impl Display for Year {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl PartialEq<u16> for Year {
+    fn eq(&self, other: &u16) -> bool {
+        self.0.eq(other)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl FromStr for Year {
+    type Err = YearFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let year = s.parse().map_err(|_| YearFromStrError)?;
+        Self::new(year).ok_or(YearFromStrError)
+    }
+}
+
+/// An error which can be returned when parsing a [`Year`].
+#[derive(Debug)]
+pub struct YearFromStrError;
+
+impl Error for YearFromStrError {}
+
+impl Display for YearFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a year of 2015 or later")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Creates a [`Year`] value in a const context.
+#[macro_export]
+macro_rules! year {
+    ($year:expr) => {{
+        const _ASSERT: () = assert!(
+            $year >= 2015 && $year <= 9999,
+            concat!(
+                "invalid year `",
+                $year,
+                "`, expecting a value of 2015 or later"
+            ),
+        );
+        $crate::template::Year::__new_unchecked($year)
+    }};
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Year;
+
+    #[test]
+    fn parses_years() {
+        assert_eq!("2024".parse::<Year>().unwrap(), 2024);
+        assert!("2014".parse::<Year>().is_err());
+        assert!("24".parse::<Year>().is_err());
+    }
+
+    #[test]
+    fn reads_years_from_bin_paths() {
+        assert_eq!(Year::from_bin_path("src/bin/2024_01.rs"), 2024);
+        assert_eq!(
+            Year::from_bin_path("/home/elf/aoc/src/bin/2015_25.rs"),
+            2015
+        );
+        assert_eq!(Year::from_bin_path("C:\\aoc\\src\\bin\\2023_07.rs"), 2023);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_paths_without_year() {
+        Year::from_bin_path("src/bin/01.rs");
+    }
+}